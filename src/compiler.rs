@@ -1,11 +1,15 @@
 use std::{
-    collections::BTreeSet,
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write as _,
     fs::{self, File},
     io::Write,
 };
 
 use crate::types::prelude::*;
-use crate::MAX_VERSION;
+use crate::{MAX_VERSION, RENAMED_VERSION};
+
+/// first pack format with the `default_block_use` advancement trigger, for clicks with an empty hand
+const DEFAULT_BLOCK_USE_VERSION: u8 = 48;
 
 // TODO: make the resource pack skeleton
 
@@ -20,15 +24,41 @@ pub fn compile(src: &mut InterRepr, namespace: &str) -> SResult<CompiledRepr> {
         load.push_str(objective);
         load.push(' ');
         load.push_str(trigger);
+        let Some(display) = src.objective_displays.get(objective) else {
+            continue;
+        };
+        if let Some(name) = &display.name {
+            load.push(' ');
+            load.push_str(name);
+        }
+        if let Some(render) = &display.render {
+            let _ = write!(load, "\nscoreboard objectives modify {objective} rendertype {render}");
+        }
+        for slot in &display.slots {
+            let _ = write!(load, "\nscoreboard objectives setdisplay {slot} {objective}");
+        }
     }
     // add all the consts
     for value in &src.constants {
-        load.push_str(&format!(
+        let _ = write!(
+            load,
             "\nscoreboard players set %__const__{value:x} dummy {value}"
-        ));
+        );
+    }
+    // create all the bossbars
+    for cmd in src.bossbars.values().flatten() {
+        load.push('\n');
+        load.push_str(&cmd.stringify(namespace));
+    }
+    // create all the teams
+    for cmd in src.teams.values().flatten() {
+        load.push('\n');
+        load.push_str(&cmd.stringify(namespace));
     }
     compiled.insert_fn("__load__", load.into());
+    compiled.insert_fn("__uninstall__", uninstall(src, namespace).into());
     compile_items(src, namespace, &mut compiled)?;
+    compile_blocks(src, namespace, &mut compiled)?;
     // put all the functions in
     for (name, statements) in &src.functions {
         let name: RStr = fmt_mc_ident(name).into();
@@ -42,15 +72,18 @@ pub fn compile(src: &mut InterRepr, namespace: &str) -> SResult<CompiledRepr> {
         });
         compiled.insert_fn(&name, fn_buf);
     }
+    for tag in src.tested_tags.difference(&src.added_tags) {
+        println!("\x1b[33mWARN\x1b[0m\tTag `{tag}` is tested but never added anywhere in the pack");
+    }
     // put all the advancements in
     for (key, value) in &src.advancements {
-        compiled.advancements.insert(key.clone(), value.to_json());
+        compiled.advancements.insert(key.clone(), value.to_json().into());
     }
     // make all the recipes
     for (name, (content, item_name)) in &src.recipes {
         let name: RStr = fmt_mc_ident(name).into();
         let item_name = fmt_mc_ident(item_name);
-        compiled.recipes.insert(name.clone(), content.clone());
+        compiled.recipes.insert(name.clone(), recipe_versions(content));
         compiled.advancements.insert(
             format!("craft/{name}").into(),
             nbt!({
@@ -66,7 +99,8 @@ pub fn compile(src: &mut InterRepr, namespace: &str) -> SResult<CompiledRepr> {
                 function: format!("{namespace}:craft/{name}")
               }}
             })
-            .to_json(),
+            .to_json()
+            .into(),
         );
         let give_fn = compiled
             .functions
@@ -81,54 +115,88 @@ pub fn compile(src: &mut InterRepr, namespace: &str) -> SResult<CompiledRepr> {
     Ok(compiled)
 }
 
+/// remove everything `__load__` creates, so the pack can be taken out of a world cleanly
+fn uninstall(src: &InterRepr, namespace: &str) -> String {
+    let mut uninstall = String::new();
+    for objective in src.objectives.keys() {
+        let _ = writeln!(uninstall, "scoreboard objectives remove {objective}");
+    }
+    for id in src.bossbars.keys() {
+        let _ = writeln!(uninstall, "bossbar remove {}", namespaced(id, namespace));
+    }
+    for team in src.teams.keys() {
+        let _ = writeln!(uninstall, "team remove {team}");
+    }
+    let _ = write!(uninstall, "say {namespace} has been uninstalled");
+    uninstall
+}
+
 fn compile_items(src: &mut InterRepr, namespace: &str, compiled: &mut CompiledRepr) -> SResult<()> {
     let mut tick_buf = Versioned::default();
     let mut using_base_item_scores = BTreeSet::new();
     for item in src.items.clone() {
         let ident = fmt_mc_ident(&item.name);
 
-        let mut give_obj = match &item.nbt {
-            Nbt::Object(obj) => obj.clone(),
-            other => return Err(format!("Expected NBT object; got {other}")),
-        };
-
-        give_obj.insert(
-            String::from("display").into(),
-            nbt!({
-                Name: format!(
-                    "{{\\\"text\\\":\\\"{}\\\",\\\"italic\\\":\\\"false\\\"}}",
-                    item.name
-                )
-            }),
-        );
-
         // make the give function
-        compiled.insert_fn(
-            &format!("give/{ident}"),
-            format!(
-                "give @s minecraft:{base}{nbt}",
-                base = item.base,
-                nbt = Nbt::Object(give_obj)
-            )
-            .into(),
-        );
+        make_give(&item, &ident, compiled)?;
 
         // make the consume function
         if !item.on_consume.is_empty() {
             make_on_consume(&item, &ident, namespace, compiled);
         }
 
-        // make the use function
+        // make the use and block break functions
         if !item.on_use.is_empty() {
-            make_on_use(
+            let on_use = if item.cooldown > 0 {
+                with_cooldown(&item, &ident, src)
+            } else {
+                item.on_use.clone()
+            };
+            make_stat_trigger(
                 &item,
                 &ident,
+                "use",
+                &format!("use/{}", item.name),
+                &on_use,
                 &mut tick_buf,
                 namespace,
                 &mut using_base_item_scores,
                 src,
             );
         }
+        if !item.on_block_break.is_empty() {
+            make_on_block_break(&item, &ident, &mut tick_buf, namespace, &mut using_base_item_scores, src, compiled);
+        }
+        if !item.on_use.is_empty() || !item.on_block_break.is_empty() {
+            make_holding_tag(&item, &ident, &mut tick_buf);
+        }
+        if item.cooldown > 0 {
+            make_cooldown_tick(&item, &ident, &mut tick_buf, namespace);
+        }
+
+        // make the melee functions
+        if !item.on_hit.is_empty() {
+            make_on_hit(&item, &ident, namespace, compiled);
+        }
+        if !item.on_kill.is_empty() {
+            make_held_trigger(&item, &ident, "kill", "minecraft:player_killed_entity", &item.on_kill, namespace, compiled);
+        }
+        if !item.on_hurt.is_empty() {
+            make_held_trigger(&item, &ident, "hurt", "minecraft:entity_hurt_player", &item.on_hurt, namespace, compiled);
+        }
+
+        // make the drop and pickup triggers
+        if !item.on_drop.is_empty() {
+            make_on_drop(&item, &ident, &mut tick_buf, namespace, &mut using_base_item_scores, src, compiled);
+        }
+        if !item.on_pickup.is_empty() {
+            make_on_pickup(&item, &ident, &mut tick_buf, namespace, &mut using_base_item_scores, src, compiled);
+        }
+
+        // make the durability functions
+        if item.durability > 0 {
+            make_durability(&item, &ident, namespace, compiled);
+        }
 
         // make the while_using function
         if !item.while_using.is_empty() {
@@ -137,55 +205,464 @@ fn compile_items(src: &mut InterRepr, namespace: &str, compiled: &mut CompiledRe
 
         // make the slot checks
         for (slot, fn_content) in &item.slot_checks {
-            let cmd = Command::execute(
+            let fn_name = format!("__internal__/slot_{slot:x}_{:x}", get_hash(fn_content));
+            let in_slot = in_slot_nbt(&item, Some(*slot));
+            let mut in_slot_check = |nbt: &str| {
+                Command::execute(
+                    &[
+                        ExecuteOption::As(Selector::a().with_property("nbt", nbt.to_string())),
+                        ExecuteOption::At(Selector::s()),
+                    ],
+                    fn_content.clone(),
+                    &fn_name,
+                    src,
+                )
+            };
+            let old = in_slot_check(in_slot.base());
+            let new = in_slot_check(in_slot.get(ITEM_COMPONENT_VERSION));
+            let cmd = old.switch_at(ITEM_COMPONENT_VERSION, new);
+            tick_buf.push('\n');
+            tick_buf.push_str_v(cmd.map(|cmd| cmd.stringify(namespace)));
+        }
+
+        // make the equip and unequip events
+        make_equip_events(&item, &ident, &mut tick_buf, namespace, src);
+    }
+    for base_score in using_base_item_scores {
+        tick_buf.push_str(&format!("\nscoreboard players reset @a {base_score}\n"));
+    }
+    if !tick_buf.is_empty() {
+        compiled.insert_fn("__tick__", tick_buf);
+    }
+    Ok(())
+}
+
+fn compile_blocks(src: &mut InterRepr, namespace: &str, compiled: &mut CompiledRepr) -> SResult<()> {
+    let mut tick_buf: Versioned<String> = Versioned::default();
+    for block in src.blocks.clone() {
+        let Some(item) = src.items.iter().find(|item| item.name == block.name).cloned() else {
+            return Err(format!("Block `{}` has no item to place it", block.name));
+        };
+        let ident = fmt_mc_ident(&block.name);
+        let stack = item_stack(&item)?;
+        let display = format!("@e[type=item_display, tag=block_{ident}]");
+
+        // swap the entity the item placed for the block
+        tick_buf.push_str(&format!(
+            "\nexecute as @e[type={}, tag=place_{ident}] at @s align xyz positioned ~0.5 ~0.5 ~0.5 run function {namespace}:block/{ident}/place",
+            item.base
+        ));
+        make_block_place(&block, &ident, &stack, namespace, compiled, src);
+
+        // right clicks are caught with advancements, so mining the block works like it does in vanilla
+        if !block.on_interact.is_empty() {
+            make_block_interact(&block, &ident, namespace, compiled, src);
+        }
+
+        // break the custom block once its block is gone; `mined_` counts who mined it this tick
+        tick_buf.push_str(&format!(
+            "\nexecute as {display} at @s unless block ~ ~ ~ {} run function {namespace}:block/{ident}/break\nscoreboard players reset @a mined_{ident}",
+            block.vanilla
+        ));
+        make_block_break(&block, &ident, &stack, namespace, compiled);
+
+        if !block.tick.is_empty() {
+            let tick = Command::execute(
                 &[
                     ExecuteOption::As(
-                        Selector::a().with_property(
-                            "nbt",
-                            nbt!({ Inventory: nbt!([nbt!({Slot: *slot, tag:item.nbt.clone()})]) })
-                                .to_string(),
-                        ),
+                        Selector::e()
+                            .with_property("type", "item_display".to_string())
+                            .with_property("tag", format!("block_{ident}")),
                     ),
                     ExecuteOption::At(Selector::s()),
                 ],
-                fn_content.clone(),
-                &format!("__internal__/slot_{slot:x}_{:x}", get_hash(fn_content)),
+                block.tick.clone(),
+                &format!("block/{ident}/tick"),
                 src,
             );
             tick_buf.push('\n');
-            tick_buf.push_str_v(cmd.map(|cmd| cmd.stringify(namespace)));
+            tick_buf.push_str_v(tick.map(|cmd| cmd.stringify(namespace)));
         }
     }
-    for base_score in using_base_item_scores {
-        tick_buf.push_str(&format!("\nscoreboard players reset @a {base_score}\n"));
-    }
     if !tick_buf.is_empty() {
         compiled.insert_fn("__tick__", tick_buf);
     }
     Ok(())
 }
 
-fn make_on_consume(item: &Item, ident: &str, namespace: &str, compiled: &mut CompiledRepr) {
-    let on_consume: RStr = format!("consume/{}", item.name).into();
-    let advancement_content = nbt!({
-      criteria: nbt!({
-        requirement: nbt!({
-          trigger: "minecraft:consume_item",
+/// set the block where its item was placed, and summon the display entity that holds its state
+fn make_block_place(
+    block: &Block,
+    ident: &str,
+    stack: &Versioned<String>,
+    namespace: &str,
+    compiled: &mut CompiledRepr,
+    src: &mut InterRepr,
+) {
+    let mut place: Versioned<String> = format!(
+        "setblock ~ ~ ~ {}\nsummon item_display ~ ~ ~ {{Tags:[\"block_{ident}\",\"block_{ident}_placed\"],item:",
+        block.vanilla
+    )
+    .into();
+    place.push_str_v(stack.clone());
+    place.push_str("}\nkill @s");
+    if !block.on_place.is_empty() {
+        let placed = Selector::e().with_property("tag", format!("block_{ident}_placed"));
+        let on_place = Command::execute(
+            &[ExecuteOption::As(placed), ExecuteOption::At(Selector::s())],
+            block.on_place.clone(),
+            &format!("block/{ident}/on_place"),
+            src,
+        );
+        place.push('\n');
+        place.push_str_v(on_place.map(|cmd| cmd.stringify(namespace)));
+    }
+    place.push_str(&format!("\ntag @e[tag=block_{ident}_placed] remove block_{ident}_placed"));
+    compiled.insert_fn(&format!("block/{ident}/place"), place);
+}
+
+/// run `on_interact` as the display entity when a player right clicks its block, with the player
+/// tagged `{ident}_interacting`
+///
+/// the advancement only says a block like it was clicked, so a ray from the player's eyes finds which one
+fn make_block_interact(
+    block: &Block,
+    ident: &str,
+    namespace: &str,
+    compiled: &mut CompiledRepr,
+    src: &mut InterRepr,
+) {
+    let name = format!("block/{ident}/interact");
+    let vanilla = block.vanilla.split_once('[').map_or(&*block.vanilla, |(id, _)| id);
+    let criterion = |trigger: &str| {
+        nbt!({
+          trigger: trigger,
           conditions: nbt!({
-            item: nbt!({
-              items: nbt!([
-                format!("minecraft:{}", item.base)
-              ]),
-              nbt: item.nbt.to_json()
-            })
+            location: nbt!([nbt!({
+              condition: "minecraft:location_check",
+              predicate: nbt!({
+                block: nbt!({
+                  blocks: nbt!([vanilla])
+                })
+              })
+            })])
           })
         })
+    };
+    let mut advancement: Versioned<String> = nbt!({
+      criteria: nbt!({
+        with_item: criterion("minecraft:item_used_on_block")
       }),
       rewards: nbt!({
-        function: format!("{namespace}:{on_consume}")
+        function: format!("{namespace}:{name}")
       })
     })
-    .to_json();
+    .to_json()
+    .into();
+    // an empty hand only counts as using a block from `default_block_use` on
+    advancement.add_version(
+        DEFAULT_BLOCK_USE_VERSION,
+        nbt!({
+          criteria: nbt!({
+            with_item: criterion("minecraft:item_used_on_block"),
+            with_hand: criterion("minecraft:default_block_use")
+          }),
+          requirements: nbt!([nbt!(["with_item", "with_hand"])]),
+          rewards: nbt!({
+            function: format!("{namespace}:{name}")
+          })
+        })
+        .to_json(),
+    );
+    compiled.advancements.insert(name.clone().into(), advancement);
+    compiled.insert_fn(
+        &name,
+        format!(
+            "advancement revoke @s only {namespace}:{name}\ntag @s add {ident}_interacting\nexecute anchored eyes positioned ^ ^ ^ run function {namespace}:block/{ident}/ray\ntag @s remove {ident}_interacting"
+        )
+        .into(),
+    );
+
+    // step along where the player is looking until it reaches a block like it
+    let on_interact = Command::execute(
+        &[
+            ExecuteOption::IfBlock {
+                invert: false,
+                pos: Coordinate::here(),
+                value: block.vanilla.clone(),
+            },
+            ExecuteOption::Align("xyz".into()),
+            ExecuteOption::Positioned(Coordinate::Linear(true, 0.5, true, 0.5, true, 0.5)),
+            ExecuteOption::As(
+                Selector::e()
+                    .with_property("type", "item_display".to_string())
+                    .with_property("tag", format!("block_{ident}"))
+                    .with_property("distance", "..0.1".to_string())
+                    .with_property("limit", "1".to_string()),
+            ),
+        ],
+        block.on_interact.clone(),
+        &format!("block/{ident}/on_interact"),
+        src,
+    );
+    let mut ray = on_interact.map(|cmd| cmd.stringify(namespace));
+    ray.push_str(&format!(
+        "\nexecute unless block ~ ~ ~ {} if entity @s[distance=..6] positioned ^ ^ ^0.1 run function {namespace}:block/{ident}/ray",
+        block.vanilla
+    ));
+    compiled.insert_fn(&format!("block/{ident}/ray"), ray);
+}
+
+/// run `on_break` as the display entity, then swap the block's vanilla drop for its item
+///
+/// the item only drops if a player mined the block in a way that gets its drops, so not in
+/// creative or with the wrong tool
+fn make_block_break(
+    block: &Block,
+    ident: &str,
+    stack: &Versioned<String>,
+    namespace: &str,
+    compiled: &mut CompiledRepr,
+) {
+    let mut break_fn = block.on_break.map_ref(|func| {
+        let mut break_fn = String::new();
+        for cmd in func {
+            break_fn.push_str(&cmd.stringify(namespace));
+            break_fn.push('\n');
+        }
+        break_fn
+    });
+    // items that were just dropped haven't ticked yet; with silk touch, the block drops itself
+    let vanilla = block.vanilla.split_once('[').map_or(&*block.vanilla, |(id, _)| id);
+    for drop in std::iter::once(vanilla).chain(block.vanilla_drop.as_deref()) {
+        break_fn.push_str(&format!(
+            "kill @e[type=item, distance=..1, nbt={{Age:0s,Item:{{id:\"{drop}\"}}}}]\n"
+        ));
+    }
+    break_fn.push_str(&format!(
+        "execute if entity @a[distance=..8, scores={{mined_{ident}=1..}}] run summon item ~ ~ ~ {{Item:"
+    ));
+    break_fn.push_str_v(stack.clone());
+    break_fn.push_str("}\nkill @s");
+    compiled.insert_fn(&format!("block/{ident}/break"), break_fn);
+}
+
+fn make_give(item: &Item, ident: &str, compiled: &mut CompiledRepr) -> SResult<()> {
+    let data = item_data(item)?;
+    let mut give: Versioned<String> = format!(
+        "give @s minecraft:{base}{nbt}",
+        base = item.base,
+        nbt = Nbt::Object(data.base().clone())
+    )
+    .into();
+    for (version, components) in data.versions() {
+        give.add_version(
+            *version,
+            format!(
+                "give @s minecraft:{base}[{components}]",
+                base = item.base,
+                components = components
+                    .iter()
+                    .map(|(k, v)| format!("{k}={v}"))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+        );
+    }
+    compiled.insert_fn(&format!("give/{ident}"), give);
+    Ok(())
+}
+
+/// the item as a stack in an entity's NBT, like a dropped item's `Item`
+fn item_stack(item: &Item) -> SResult<Versioned<String>> {
+    let data = item_data(item)?;
+    let mut stack: Versioned<String> = format!(
+        "{{id:\"minecraft:{base}\",Count:1b,tag:{nbt}}}",
+        base = item.base,
+        nbt = Nbt::Object(data.base().clone())
+    )
+    .into();
+    for (version, components) in data.versions() {
+        stack.add_version(
+            *version,
+            format!(
+                "{{id:\"minecraft:{base}\",count:1,components:{{{components}}}}}",
+                base = item.base,
+                components = components
+                    .iter()
+                    .map(|(k, v)| format!("\"minecraft:{k}\":{v}"))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+        );
+    }
+    Ok(stack)
+}
+
+/// the item's tag as it's given, and its item components from [`ITEM_COMPONENT_VERSION`] on
+fn item_data(item: &Item) -> SResult<Versioned<BTreeMap<RStr, Nbt>>> {
+    // the uses left are only given to the item, not matched, since they change
+    let mut item = item.clone();
+    if let (true, Nbt::Object(obj)) = (item.durability > 0, &mut item.nbt) {
+        if let Some(Nbt::Object(tag)) = obj.get_mut("tag") {
+            tag.insert("durability".into(), item.durability.into());
+        }
+    }
+    let item = &item;
+    let mut give_obj = match &item.nbt {
+        Nbt::Object(obj) => obj.clone(),
+        other => return Err(format!("Expected NBT object; got {other}")),
+    };
+
+    let mut display = match item.properties.base().get("display") {
+        Some(Nbt::Object(display)) => display.clone(),
+        _ => BTreeMap::new(),
+    };
+    display.insert(
+        "Name".into(),
+        format!(
+            "{{\\\"text\\\":\\\"{}\\\",\\\"italic\\\":\\\"false\\\"}}",
+            item.name
+        )
+        .into(),
+    );
+    give_obj.extend(item.properties.base().clone());
+    give_obj.insert("display".into(), Nbt::Object(display));
+
+    let mut data: Versioned<BTreeMap<RStr, Nbt>> = give_obj.into();
+    let nbt_components = nbt_components(item);
+    for (version, properties) in item.properties.versions() {
+        let mut components = properties.clone();
+        components.insert(
+            "custom_name".into(),
+            nbt!({text: item.name.clone(), italic: false})
+                .to_json()
+                .replace('"', "\\\"")
+                .into(),
+        );
+        components.extend(nbt_components.clone());
+        data.add_version(*version, components);
+    }
+    Ok(data)
+}
+
+/// the item's `nbt` as item components, with its `tag` as custom data
+fn nbt_components(item: &Item) -> BTreeMap<RStr, Nbt> {
+    let mut components = BTreeMap::new();
+    let Nbt::Object(obj) = &item.nbt else {
+        return components;
+    };
+    for (k, v) in obj {
+        match &**k {
+            "tag" => {
+                components.insert("custom_data".into(), v.clone());
+            }
+            "customModelData" | "CustomModelData" => {
+                components.insert("custom_model_data".into(), v.clone());
+            }
+            "EntityTag" => {
+                components.insert("entity_data".into(), v.clone());
+            }
+            other => println!("\x1b[33mWARN\x1b[0m\tItem `{}` has `{other}` in its nbt, which isn't written from pack format {ITEM_COMPONENT_VERSION} on; use a typed property instead", item.name),
+        }
+    }
+    components
+}
+
+/// the item's `tag`, which is its `custom_data` component with item components
+fn custom_data(item: &Item) -> Nbt {
+    match &item.nbt {
+        Nbt::Object(obj) => obj.get("tag").cloned().unwrap_or_default(),
+        _ => Nbt::default(),
+    }
+}
+
+/// the NBT that an item entry like `SelectedItem` or `Inventory` has when it's this item,
+/// with item components
+fn component_match(item: &Item, slot: Option<i8>) -> Nbt {
+    let custom_data = custom_data(item);
+    let mut nbt = BTreeMap::from([
+        ("id".into(), Nbt::from(format!("minecraft:{}", item.base))),
+        (
+            "components".into(),
+            Nbt::Object(BTreeMap::from([("\"minecraft:custom_data\"".into(), custom_data)])),
+        ),
+    ]);
+    if let Some(slot) = slot {
+        nbt.insert("Slot".into(), slot.into());
+    }
+    Nbt::Object(nbt)
+}
+
+/// the item predicate in an advancement's conditions that matches this item
+fn item_predicate(item: &Item) -> Versioned<Nbt> {
+    let items = nbt!([format!("minecraft:{}", item.base)]);
+    let mut predicate: Versioned<Nbt> = nbt!({
+        items: items.clone(),
+        nbt: item.nbt.to_json()
+    })
+    .into();
+    let custom_data = custom_data(item);
+    predicate.add_version(
+        ITEM_COMPONENT_VERSION,
+        Nbt::Object(BTreeMap::from([
+            ("items".into(), items),
+            (
+                "predicates".into(),
+                Nbt::Object(BTreeMap::from([(
+                    "minecraft:custom_data".into(),
+                    Nbt::from(custom_data.to_string()),
+                )])),
+            ),
+        ])),
+    );
+    predicate
+}
+
+/// get a recipe's JSON for each pack format, since results became item stacks with an `id` with item components
+fn recipe_versions(recipe: &Nbt) -> Versioned<String> {
+    let mut versioned: Versioned<String> = recipe.to_json().into();
+    let Nbt::Object(obj) = recipe else {
+        return versioned;
+    };
+    let mut obj = obj.clone();
+    let result = match obj.remove("result") {
+        Some(Nbt::Object(mut result)) => {
+            if let Some(id) = result.remove("item") {
+                result.insert("id".into(), id);
+            }
+            Nbt::Object(result)
+        }
+        Some(Nbt::String(id)) => {
+            let count = obj.remove("count").unwrap_or(Nbt::Integer(1));
+            nbt!({id: id, count: count})
+        }
+        Some(other) => other,
+        None => return versioned,
+    };
+    obj.insert("result".into(), result);
+    versioned.add_version(ITEM_COMPONENT_VERSION, Nbt::Object(obj).to_json());
+    versioned
+}
+
+fn make_on_consume(item: &Item, ident: &str, namespace: &str, compiled: &mut CompiledRepr) {
+    let on_consume: RStr = format!("consume/{}", item.name).into();
+    let advancement_content = item_predicate(item).map(|predicate| {
+        nbt!({
+          criteria: nbt!({
+            requirement: nbt!({
+              trigger: "minecraft:consume_item",
+              conditions: nbt!({
+                item: predicate
+              })
+            })
+          }),
+          rewards: nbt!({
+            function: format!("{namespace}:{on_consume}")
+          })
+        })
+        .to_json()
+    });
     let consume_fn = item.on_consume.map_ref(|func| {
         let mut consume_fn = format!("advancement revoke @s only {namespace}:consume/{ident}");
         for cmd in func {
@@ -200,66 +677,562 @@ fn make_on_consume(item: &Item, ident: &str, namespace: &str, compiled: &mut Com
     compiled.insert_fn(&on_consume, consume_fn);
 }
 
-fn make_on_use(
+/// run `body` as players holding the item whose `stat` score went up this tick, like `use` for `on_use`
+#[allow(clippy::too_many_arguments)]
+fn make_stat_trigger(
     item: &Item,
     ident: &str,
+    stat: &str,
+    fn_name: &str,
+    body: &VecCmd,
     tick_buf: &mut Versioned<String>,
     namespace: &str,
-    using_base_item_scores: &mut BTreeSet<String>,
+    base_item_scores: &mut BTreeSet<String>,
     src: &mut InterRepr,
 ) {
-    let on_use = format!("use/{}", item.name);
-    let using_base = format!("use_{}", item.base);
+    let stat_base = format!("{stat}_{}", item.base);
     let holding_item = format!("holding_{ident}");
     let execute_fn = Command::execute(
         &[
             ExecuteOption::As(Selector {
                 selector_type: SelectorType::A,
                 args: [
-                    ("tag".into(), holding_item.clone()),
-                    ("scores".into(), format!("{{{using_base}=1}}")),
+                    ("tag".into(), holding_item),
+                    ("scores".into(), format!("{{{stat_base}=1}}")),
                 ]
                 .into_iter()
                 .collect(),
             }),
             ExecuteOption::At(Selector::s()),
         ],
-        item.on_use.clone(),
-        &on_use,
+        body.clone(),
+        fn_name,
         src,
     );
     tick_buf.push('\n');
     tick_buf.push_str_v(execute_fn.map(|cmd| cmd.stringify(namespace)));
+    base_item_scores.insert(stat_base);
+}
+
+/// run `on_block_break` when the player uses the item without hitting anything with it
+///
+/// tools and weapons count as used both when they break a block and when they hit an entity, so
+/// an advancement tags the players that hit something this tick
+fn make_on_block_break(
+    item: &Item,
+    ident: &str,
+    tick_buf: &mut Versioned<String>,
+    namespace: &str,
+    base_item_scores: &mut BTreeSet<String>,
+    src: &mut InterRepr,
+    compiled: &mut CompiledRepr,
+) {
+    let melee = format!("{ident}_melee");
+    let tag_melee: VecCmd = vec![Command::Tag {
+        target: Selector::s(),
+        add: true,
+        tag: melee.clone().into(),
+    }]
+    .into();
+    make_held_trigger(item, ident, "melee", "minecraft:player_hurt_entity", &tag_melee, namespace, compiled);
+    let on_block_break = Command::execute(
+        &[ExecuteOption::IfEntity {
+            invert: false,
+            selector: Selector::s().with_property("tag", format!("!{melee}")),
+        }],
+        item.on_block_break.clone(),
+        &format!("mine/{ident}"),
+        src,
+    );
+    make_stat_trigger(
+        item,
+        ident,
+        "mine",
+        &format!("mine/{ident}/trigger"),
+        &on_block_break.into_vec(),
+        tick_buf,
+        namespace,
+        base_item_scores,
+        src,
+    );
+    tick_buf.push_str(&format!("\ntag @a remove {melee}"));
+}
+
+/// make `on_use` only run when the player's cooldown is over, and start it again,
+/// running `on_cooldown` instead while it isn't
+fn with_cooldown(item: &Item, ident: &str, src: &mut InterRepr) -> VecCmd {
+    let objective: RStr = format!("cd_{ident}").into();
+    let cooling_down = |invert| ExecuteOption::IfScoreMatches {
+        invert,
+        target: "@s".into(),
+        objective: objective.clone(),
+        lower: Some(1),
+        upper: None,
+    };
+    let mut ready: VecCmd = vec![Command::ScoreSet {
+        target: "@s".into(),
+        objective: objective.clone(),
+        value: item.cooldown,
+    }]
+    .into();
+    ready.extend(item.on_use.clone());
+    let mut on_use = VecCmd::default();
+    if !item.on_cooldown.is_empty() {
+        on_use.extend(
+            Command::execute(
+                &[cooling_down(false)],
+                item.on_cooldown.clone(),
+                &format!("cooldown/{ident}/blocked"),
+                src,
+            )
+            .into_vec(),
+        );
+    }
+    on_use.extend(
+        Command::execute(
+            &[cooling_down(true)],
+            ready,
+            &format!("cooldown/{ident}/ready"),
+            src,
+        )
+        .into_vec(),
+    );
+    on_use
+}
+
+/// count down everyone's cooldown for the item, showing what's left if the item asks for it
+fn make_cooldown_tick(item: &Item, ident: &str, tick_buf: &mut Versioned<String>, namespace: &str) {
+    let objective = format!("cd_{ident}");
+    let cooling_down = Selector::a().with_property("scores", format!("{{{objective}=1..}}"));
+    if item.show_cooldown {
+        let text = nbt!([
+            nbt!({text: format!("{} ", item.name)}),
+            nbt!({score: nbt!({name: "@s", objective: objective.clone()})}),
+            nbt!({text: "t"})
+        ]);
+        let show = Command::Execute {
+            options: vec![ExecuteOption::As(cooling_down.clone())],
+            cmd: Box::new(Command::Title {
+                target: Selector::s(),
+                kind: "actionbar".into(),
+                text: text.to_json().into(),
+            }),
+        };
+        tick_buf.push('\n');
+        tick_buf.push_str(&show.stringify(namespace));
+    }
+    let count_down = Command::ScoreAdd {
+        target: cooling_down.to_string().into(),
+        objective: objective.into(),
+        value: -1,
+    };
     tick_buf.push('\n');
-    tick_buf.push_str(&format!(
-                "tag @a remove {holding_item}\ntag @a[nbt={{SelectedItem:{{id:\"minecraft:{}\",tag:{}}}}}] add {holding_item}\n",
-                item.base,
-                item.nbt
-            ));
-    using_base_item_scores.insert(using_base);
+    tick_buf.push_str(&count_down.stringify(namespace));
+}
+
+/// tag the players holding the item, for the triggers that are checked with statistics
+fn make_holding_tag(item: &Item, ident: &str, tick_buf: &mut Versioned<String>) {
+    let holding_item = format!("holding_{ident}");
+    tick_buf.push('\n');
+    let mut holding: Versioned<String> = format!(
+        "tag @a remove {holding_item}\ntag @a[nbt={{SelectedItem:{{id:\"minecraft:{}\",tag:{}}}}}] add {holding_item}\n",
+        item.base,
+        item.nbt
+    )
+    .into();
+    holding.add_version(
+        ITEM_COMPONENT_VERSION,
+        format!(
+            "tag @a remove {holding_item}\ntag @a[nbt={{SelectedItem:{}}}] add {holding_item}\n",
+            component_match(item, None)
+        ),
+    );
+    tick_buf.push_str_v(holding);
+}
+
+/// run `on_equip` and `on_unequip` once each time the item goes in or out of a slot,
+/// remembering who has it in the slot with a tag
+fn make_equip_events(
+    item: &Item,
+    ident: &str,
+    tick_buf: &mut Versioned<String>,
+    namespace: &str,
+    src: &mut InterRepr,
+) {
+    for (slot_key, (slot, on_equip, on_unequip)) in &item.equip_events {
+        let equipped = format!("equipped_{ident}_{slot_key}");
+        let in_slot = in_slot_nbt(item, *slot);
+        for (event, body, add) in [("equip", on_equip, true), ("unequip", on_unequip, false)] {
+            let mut fn_content: VecCmd = vec![Command::Tag {
+                target: Selector::s(),
+                add,
+                tag: equipped.clone().into(),
+            }]
+            .into();
+            fn_content.extend(body.clone());
+            let fn_name = format!("{event}/{ident}/{slot_key}");
+            let mut transition = |nbt: &str| {
+                let (tag, nbt) = if add {
+                    (format!("!{equipped}"), nbt.to_string())
+                } else {
+                    (equipped.clone(), format!("!{nbt}"))
+                };
+                Command::execute(
+                    &[
+                        ExecuteOption::As(
+                            Selector::a()
+                                .with_property("tag", tag)
+                                .with_property("nbt", nbt),
+                        ),
+                        ExecuteOption::At(Selector::s()),
+                    ],
+                    fn_content.clone(),
+                    &fn_name,
+                    src,
+                )
+            };
+            let old = transition(in_slot.base());
+            let new = transition(in_slot.get(ITEM_COMPONENT_VERSION));
+            let cmd = old.switch_at(ITEM_COMPONENT_VERSION, new);
+            tick_buf.push('\n');
+            tick_buf.push_str_v(cmd.map(|cmd| cmd.stringify(namespace)));
+        }
+    }
+}
+
+/// the `nbt` selector argument for players with the item in a slot, or selected if there's no slot
+fn in_slot_nbt(item: &Item, slot: Option<i8>) -> Versioned<String> {
+    let (old, new) = slot.map_or_else(
+        || {
+            (
+                nbt!({ SelectedItem: nbt!({id: format!("minecraft:{}", item.base), tag: item.nbt.clone()}) }),
+                nbt!({ SelectedItem: component_match(item, None) }),
+            )
+        },
+        |number| {
+            (
+                nbt!({ Inventory: nbt!([nbt!({Slot: number, tag: item.nbt.clone()})]) }),
+                nbt!({ Inventory: nbt!([component_match(item, slot)]) }),
+            )
+        },
+    );
+    let mut nbt: Versioned<String> = old.to_string().into();
+    nbt.add_version(ITEM_COMPONENT_VERSION, new.to_string());
+    nbt
+}
+
+/// run `on_drop` as players who dropped the item this tick, with the item entities they dropped tagged `<item>_dropped`
+#[allow(clippy::too_many_arguments)]
+fn make_on_drop(
+    item: &Item,
+    ident: &str,
+    tick_buf: &mut Versioned<String>,
+    namespace: &str,
+    base_item_scores: &mut BTreeSet<String>,
+    src: &mut InterRepr,
+    compiled: &mut CompiledRepr,
+) {
+    let stat_base = format!("drop_{}", item.base);
+    let fn_name = format!("drop/{ident}");
+    let dropped_tag = format!("{ident}_dropped");
+    let dropped = Selector::e().with_property("tag", dropped_tag.clone());
+    // the statistic also counts vanilla items with the same base, which don't get tagged
+    let handler = Command::execute(
+        &[ExecuteOption::IfEntity {
+            invert: false,
+            selector: dropped.clone(),
+        }],
+        item.on_drop.clone(),
+        &format!("{fn_name}/handler"),
+        src,
+    )
+    .map(|cmd| cmd.stringify(namespace));
+    let drop_fn = |item_nbt: String, handler: &str| {
+        // dropped items haven't ticked yet, so they're the only ones this young
+        format!(
+            "tag @e[type=item, distance=..3, nbt={{Age:0s,Item:{item_nbt}}}] add {dropped_tag}\n{handler}\ntag {dropped} remove {dropped_tag}"
+        )
+    };
+    let mut drop_fns: Versioned<String> = drop_fn(
+        format!("{{id:\"minecraft:{}\",tag:{}}}", item.base, item.nbt),
+        handler.base(),
+    )
+    .into();
+    drop_fns.add_version(
+        ITEM_COMPONENT_VERSION,
+        drop_fn(
+            component_match(item, None).to_string(),
+            handler.get(ITEM_COMPONENT_VERSION),
+        ),
+    );
+    compiled.insert_fn(&fn_name, drop_fns);
+    let run = Command::Execute {
+        options: vec![
+            ExecuteOption::As(Selector::a().with_property("scores", format!("{{{stat_base}=1..}}"))),
+            ExecuteOption::At(Selector::s()),
+        ],
+        cmd: Box::new(Command::Function(fn_name.into())),
+    };
+    tick_buf.push('\n');
+    tick_buf.push_str(&run.stringify(namespace));
+    base_item_scores.insert(stat_base);
+}
+
+/// run `on_pickup` as players who picked up the item's base this tick and now have more of the item
+///
+/// the statistic doesn't say which stack was picked up, so each player's count of the item is
+/// kept up to date every tick to tell picking up this item from picking up a vanilla one
+fn make_on_pickup(
+    item: &Item,
+    ident: &str,
+    tick_buf: &mut Versioned<String>,
+    namespace: &str,
+    base_item_scores: &mut BTreeSet<String>,
+    src: &mut InterRepr,
+    compiled: &mut CompiledRepr,
+) {
+    let stat_base = format!("pickup_{}", item.base);
+    let count = format!("has_{ident}");
+    let fn_name = format!("pickup/{ident}");
+    let handler = Command::execute(
+        &[
+            ExecuteOption::IfEntity {
+                invert: false,
+                selector: Selector::s().with_property("scores", format!("{{{stat_base}=1..}}")),
+            },
+            ExecuteOption::IfScoreSource {
+                invert: false,
+                target: "@s".into(),
+                target_objective: count.clone().into(),
+                operation: Operation::RCaret,
+                source: "%__count__".into(),
+                source_objective: count.clone().into(),
+            },
+        ],
+        item.on_pickup.clone(),
+        &format!("{fn_name}/handler"),
+        src,
+    )
+    .map(|cmd| cmd.stringify(namespace));
+    // the new count is swapped into the player's score, leaving the old one to compare against
+    let pickup_fn = |item_arg: String, handler: &str| {
+        format!(
+            "execute store result score %__count__ {count} run clear @s {item_arg} 0
+scoreboard players operation %__count__ {count} >< @s {count}
+{handler}"
+        )
+    };
+    let mut pickup_fns: Versioned<String> =
+        pickup_fn(format!("minecraft:{}{}", item.base, item.nbt), handler.base()).into();
+    pickup_fns.add_version(
+        ITEM_COMPONENT_VERSION,
+        pickup_fn(
+            format!("minecraft:{}[minecraft:custom_data~{}]", item.base, custom_data(item)),
+            handler.get(ITEM_COMPONENT_VERSION),
+        ),
+    );
+    compiled.insert_fn(&fn_name, pickup_fns);
+    let run = Command::Execute {
+        options: vec![ExecuteOption::As(Selector::a()), ExecuteOption::At(Selector::s())],
+        cmd: Box::new(Command::Function(fn_name.into())),
+    };
+    tick_buf.push('\n');
+    tick_buf.push_str(&run.stringify(namespace));
+    base_item_scores.insert(stat_base);
+}
+
+/// make the function that uses up one of the held item's uses, and the item modifier that writes it back
+fn make_durability(item: &Item, ident: &str, namespace: &str, compiled: &mut CompiledRepr) {
+    let objective = format!("dur_{ident}");
+    let name = format!("durability/{ident}");
+    let durability_line = nbt!([
+        nbt!({text: "Durability: ", color: "gray", italic: false}),
+        nbt!({score: nbt!({name: "@s", objective: objective.clone()})}),
+        nbt!({text: format!(" / {}", item.durability)})
+    ]);
+    // older versions can only replace the whole lore, so the user's lines are written back with it
+    let mut lore = item.lore.clone();
+    lore.push(durability_line.clone());
+    let storage = format!("{namespace}:durability");
+    let source = nbt!({type: "minecraft:storage", source: storage.clone()});
+    #[allow(clippy::cast_precision_loss)]
+    let set_damage = nbt!({
+        function: "minecraft:set_damage",
+        damage: nbt!({
+            type: "minecraft:score",
+            target: "this",
+            score: objective.clone(),
+            scale: 1.0 / item.durability as f32
+        })
+    });
+    let mut modifier: Versioned<String> = nbt!([
+        nbt!({
+            function: "minecraft:copy_nbt",
+            source: source.clone(),
+            ops: nbt!([nbt!({source: "__durability__", target: "tag.durability", op: "replace"})])
+        }),
+        nbt!({function: "minecraft:set_lore", entity: "this", replace: true, lore: lore.clone()}),
+        set_damage.clone()
+    ])
+    .to_json()
+    .into();
+    modifier.add_version(
+        ITEM_COMPONENT_VERSION,
+        nbt!([
+            nbt!({
+                function: "minecraft:copy_custom_data",
+                source: source,
+                ops: nbt!([nbt!({source: "__durability__", target: "durability", op: "replace"})])
+            }),
+            nbt!({
+                function: "minecraft:set_lore",
+                entity: "this",
+                mode: "replace_section",
+                offset: i32::try_from(item.lore.len()).unwrap_or(i32::MAX),
+                lore: nbt!([durability_line])
+            }),
+            set_damage
+        ])
+        .to_json(),
+    );
+    compiled.item_modifiers.insert(name.clone().into(), modifier);
+
+    let use_up = |path: &str| {
+        format!(
+            "execute store result score @s {objective} run data get entity @s SelectedItem.{path}
+scoreboard players remove @s {objective} 1
+execute store result storage {storage} __durability__ int 1 run scoreboard players get @s {objective}
+execute if score @s {objective} matches 1.. run item modify entity @s weapon.mainhand {namespace}:{name}
+execute if score @s {objective} matches ..0 run function {namespace}:{name}/break"
+        )
+    };
+    let mut use_up_fn: Versioned<String> = use_up("tag.tag.durability").into();
+    use_up_fn.add_version(
+        ITEM_COMPONENT_VERSION,
+        use_up("components.\"minecraft:custom_data\".durability"),
+    );
+    compiled.insert_fn(&name, use_up_fn);
+
+    let break_fn = item.on_break.map_ref(|func| {
+        let mut break_fn = format!(
+            "item replace entity @s weapon.mainhand with air\nplaysound {} player @a ~ ~ ~",
+            item.break_sound
+        );
+        for cmd in func {
+            break_fn.push('\n');
+            break_fn.push_str(&cmd.stringify(namespace));
+        }
+        break_fn
+    });
+    compiled.insert_fn(&format!("{name}/break"), break_fn);
+}
+
+/// run `on_hit` with the entities that were hit tagged `<item>_victim`
+///
+/// other things could have been hurt this tick too, so the victims are the ones whose attacker is the holder
+fn make_on_hit(item: &Item, ident: &str, namespace: &str, compiled: &mut CompiledRepr) {
+    let attacker = format!("{ident}_attacker");
+    let victim = format!("{ident}_victim");
+    let objective = format!("hit_{ident}");
+    let just_hurt = Selector::e()
+        .with_property("distance", "..8".to_string())
+        .with_property("nbt", String::from("{HurtTime:10s}"));
+    let mut on_hit: VecCmd = vec![
+        Command::Tag {
+            target: Selector::s(),
+            add: true,
+            tag: attacker.clone().into(),
+        },
+        // `store ... @s` is the hurt entity, while `if entity @s` is its attacker
+        Command::Raw(format!("execute as {just_hurt} store success score @s {objective} on attacker if entity @s[tag={attacker}]").into()),
+        Command::Tag {
+            target: Selector::e()
+                .with_property("distance", "..8".to_string())
+                .with_property("scores", format!("{{{objective}=1}}")),
+            add: true,
+            tag: victim.clone().into(),
+        },
+        Command::Raw(format!("scoreboard players reset @e[distance=..8] {objective}").into()),
+    ]
+    .into();
+    on_hit.extend(item.on_hit.clone());
+    on_hit.extend(VecCmd::from(vec![
+        Command::Tag {
+            target: Selector::e().with_property("tag", victim.clone()),
+            add: false,
+            tag: victim.into(),
+        },
+        Command::Tag {
+            target: Selector::s(),
+            add: false,
+            tag: attacker.into(),
+        },
+    ]));
+    make_held_trigger(item, ident, "hit", "minecraft:player_hurt_entity", &on_hit, namespace, compiled);
+}
+
+/// make an advancement that runs `body` as the player when `trigger` fires while they're holding the item,
+/// and revokes itself so it can fire again
+fn make_held_trigger(
+    item: &Item,
+    ident: &str,
+    kind: &str,
+    trigger: &str,
+    body: &VecCmd,
+    namespace: &str,
+    compiled: &mut CompiledRepr,
+) {
+    let name = format!("{kind}/{ident}");
+    let advancement_content = item_predicate(item).map(|predicate| {
+        nbt!({
+          criteria: nbt!({
+            requirement: nbt!({
+              trigger: trigger,
+              conditions: nbt!({
+                player: nbt!({
+                  equipment: nbt!({
+                    mainhand: predicate
+                  })
+                })
+              })
+            })
+          }),
+          rewards: nbt!({
+            function: format!("{namespace}:{name}")
+          })
+        })
+        .to_json()
+    });
+    let trigger_fn = body.map_ref(|func| {
+        let mut trigger_fn = format!("advancement revoke @s only {namespace}:{name}");
+        for cmd in func {
+            trigger_fn.push('\n');
+            trigger_fn.push_str(&cmd.stringify(namespace));
+        }
+        trigger_fn
+    });
+    compiled.advancements.insert(name.clone().into(), advancement_content);
+    compiled.insert_fn(&name, trigger_fn);
 }
 
 fn make_while_using(item: &Item, ident: &str, namespace: &str, compiled: &mut CompiledRepr) {
     let while_using: RStr = format!("using/{}", item.name).into();
-    let advancement_content = nbt!({
-      criteria: nbt!({
-        requirement: nbt!({
-          trigger: "minecraft:using_item",
-          conditions: nbt!({
-            item: nbt!({
-              items: nbt!([
-                format!("minecraft:{}", item.base)
-              ]),
-              nbt: item.nbt.clone()
+    let advancement_content = item_predicate(item).map(|predicate| {
+        nbt!({
+          criteria: nbt!({
+            requirement: nbt!({
+              trigger: "minecraft:using_item",
+              conditions: nbt!({
+                item: predicate
+              })
             })
+          }),
+          rewards: nbt!({
+            function: format!("{namespace}:{while_using}")
           })
         })
-      }),
-      rewards: nbt!({
-        function: format!("{namespace}:{while_using}")
-      })
-    })
-    .to_json();
+        .to_json()
+    });
     let on_use_fn = item.while_using.map_ref(|func| {
         let mut on_use_fn_content = format!("advancement revoke @s only {namespace}:use/{ident}");
         for cmd in func {
@@ -275,52 +1248,43 @@ fn make_while_using(item: &Item, ident: &str, namespace: &str, compiled: &mut Co
 }
 
 pub fn write(repr: &CompiledRepr, parent: &str, nmsp: &str) -> Result<(), std::io::Error> {
-    let _ = fs::remove_dir_all(&format!("{parent}{nmsp}"));
+    let _ = fs::remove_dir_all(format!("{parent}{nmsp}"));
+    let root = format!("{parent}{nmsp}");
     let mut versions = BTreeSet::new();
     for (path, contents) in &repr.functions {
-        if !contents.base().is_empty() {
-            let mut file = create_file_with_parent_dirs(&format!(
-                "{parent}{nmsp}/data/{nmsp}/functions/{path}.mcfunction"
-            ))?;
-            write!(file, "{}", contents.base())?;
-            if &**path == "__tick__" {
-                let mut tick = create_file_with_parent_dirs(&format!(
-                    "{parent}{nmsp}/data/minecraft/tags/functions/tick.json"
-                ))?;
-                write!(tick, "{{\"values\":[\"{nmsp}:__tick__\"]}}")?;
-            }
-            if &**path == "__load__" {
-                let mut load = create_file_with_parent_dirs(&format!(
-                    "{parent}{nmsp}/data/minecraft/tags/functions/load.json"
-                ))?;
-                write!(load, "{{\"values\":[\"{nmsp}:__load__\"]}}")?;
-            }
+        write_versioned(&root, (nmsp, "functions"), &format!("{path}.mcfunction"), contents, &mut versions)?;
+        if contents.base().is_empty() {
+            continue;
         }
-        for (version, content) in contents.versions() {
-            versions.insert(*version);
-            let mut file = create_file_with_parent_dirs(&format!(
-                "{parent}{nmsp}/fmt_{version}/data/{nmsp}/functions/{path}.mcfunction"
-            ))?;
-            write!(file, "{content}")?;
+        for tag in ["tick", "load"] {
+            if **path == *format!("__{tag}__") {
+                write_versioned(
+                    &root,
+                    ("minecraft", "tags/functions"),
+                    &format!("{tag}.json"),
+                    &format!("{{\"values\":[\"{nmsp}:__{tag}__\"]}}").into(),
+                    &mut versions,
+                )?;
+            }
         }
     }
-    for (path, contents) in &repr.advancements {
-        let mut file = create_file_with_parent_dirs(&format!(
-            "{parent}{nmsp}/data/{nmsp}/advancements/{path}.json"
-        ))?;
-        write!(file, "{contents}")?;
-    }
-    for (path, contents) in &repr.recipes {
-        let mut file = create_file_with_parent_dirs(&format!(
-            "{parent}{nmsp}/data/{nmsp}/recipes/{path}.json"
-        ))?;
-        write!(file, "{contents}")?;
+    for (kind, files) in [
+        ("advancements", &repr.advancements),
+        ("recipes", &repr.recipes),
+        ("item_modifiers", &repr.item_modifiers),
+    ] {
+        for (path, contents) in files {
+            write_versioned(&root, (nmsp, kind), &format!("{path}.json"), contents, &mut versions)?;
+        }
     }
     for (path, contents) in &repr.loot_tables {
-        let mut file = create_file_with_parent_dirs(&format!(
-            "{parent}{nmsp}/data/{nmsp}/loot_tables/{path}.json"
-        ))?;
-        write!(file, "{contents}")?;
+        write_versioned(
+            &root,
+            (nmsp, "loot_tables"),
+            &format!("{path}.json"),
+            &contents.to_string().into(),
+            &mut versions,
+        )?;
     }
     let mut mcmeta = create_file_with_parent_dirs(&format!("{parent}{nmsp}/pack.mcmeta"))?;
     #[allow(clippy::cast_lossless)]
@@ -329,7 +1293,7 @@ pub fn write(repr: &CompiledRepr, parent: &str, nmsp: &str) -> Result<(), std::i
         .map(|version| {
             nbt!({
                 directory: format!("fmt_{version}"),
-                formats: nbt!({min_inclusive: version as i32})
+                formats: nbt!({min_inclusive: version as i32, max_inclusive: version.max(MAX_VERSION) as i32})
             })
         })
         .rev()
@@ -347,6 +1311,37 @@ pub fn write(repr: &CompiledRepr, parent: &str, nmsp: &str) -> Result<(), std::i
     Ok(())
 }
 
+/// write a file to `data/{namespace}/{kind}/{path}` and to an overlay for each version it changes
+/// in; from `RENAMED_VERSION` on, the kind drops its plural, like `functions/` to `function/`
+fn write_versioned(
+    root: &str,
+    (namespace, kind): (&str, &str),
+    path: &str,
+    contents: &Versioned<String>,
+    versions: &mut BTreeSet<u8>,
+) -> Result<(), std::io::Error> {
+    let renamed = kind.strip_suffix('s').unwrap_or(kind);
+    if !contents.base().is_empty() {
+        let mut file = create_file_with_parent_dirs(&format!("{root}/data/{namespace}/{kind}/{path}"))?;
+        write!(file, "{}", contents.base())?;
+    }
+    let mut overlays = contents.versions().iter().collect::<BTreeMap<_, _>>();
+    if !contents.get(RENAMED_VERSION).is_empty() {
+        overlays
+            .entry(&RENAMED_VERSION)
+            .or_insert_with(|| contents.get(RENAMED_VERSION));
+    }
+    for (version, content) in overlays {
+        versions.insert(*version);
+        let kind = if *version >= RENAMED_VERSION { renamed } else { kind };
+        let mut file = create_file_with_parent_dirs(&format!(
+            "{root}/fmt_{version}/data/{namespace}/{kind}/{path}"
+        ))?;
+        write!(file, "{content}")?;
+    }
+    Ok(())
+}
+
 fn create_file_with_parent_dirs(filename: &str) -> Result<File, std::io::Error> {
     let parent_dir = std::path::Path::new(filename).parent().unwrap();
     fs::create_dir_all(parent_dir)?;
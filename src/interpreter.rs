@@ -1,4 +1,5 @@
 use std::{
+    borrow::Cow,
     collections::BTreeSet,
     path::{Path, PathBuf},
 };
@@ -7,12 +8,20 @@ use crate::{types::prelude::*, Config};
 
 /// handles annotations like `@item {...}`
 mod annotations;
+/// handles entity attributes like `@s::attr.generic.max_health`
+mod attribute;
 /// handles blocks of the form `if <condition> {...}`
 mod block;
+/// handles bossbar declarations like `@bossbar fight {...}` and assignments like `bossbar::fight.value = hp`
+mod bossbar;
 /// handles operations like `counter += 1;`
 mod operation;
+/// handles entity pseudo-properties like `@s::y` or `@s::health`
+mod property;
 /// handles selector blocks like `as @s {...}`
 mod selector_block;
+/// handles team declarations like `@team red {...}` and membership like `team red += @a`
+mod team;
 /// handles operations like `counter := @function "get_count"` or `success ?= @function "try_something"`
 mod store;
 
@@ -24,6 +33,7 @@ pub fn interpret(
 ) -> SResult<InterRepr> {
     let mut state = InterRepr::new(config);
     inner_interpret(src, &mut state, path, src_files, config)?;
+    state.declare_used_objectives(config)?;
     Ok(state)
 }
 
@@ -38,9 +48,15 @@ fn inner_interpret(
         // []
         Syntax::Array(statements) => {
             let mut commands_buf = VecCmd::default();
-            for statement in statements.iter() {
+            let outer_broke = core::mem::take(&mut state.broke_loop);
+            for (i, statement) in statements.iter().enumerate() {
                 commands_buf.extend(inner_interpret(statement, state, path, src_files, config)?);
+                if state.broke_loop && i + 1 < statements.len() {
+                    commands_buf.extend(after_break(&statements[i + 1..], state, path, src_files, config)?);
+                    break;
+                }
             }
+            state.broke_loop |= outer_broke;
             return Ok(commands_buf);
         }
         Syntax::BinaryOp {
@@ -71,19 +87,76 @@ fn inner_interpret(
         Syntax::Annotation(name, properties) => {
             return annotations::annotations(name, properties, state, path, src_files, config)
         }
+        // break out of a `for .. in @selector` loop
+        Syntax::Identifier(ident) if &**ident == "break" => {
+            let Some(tag) = state.for_each_tags.last() else {
+                return Err(String::from("`break` can only be used inside a `for {var} in {selector}` loop"))
+            };
+            let commands = vec![
+                Command::Raw(format!("tag @e[tag={tag}] remove {tag}").into()),
+                Command::score_init(&format!("%{tag}"), &config.dummy_objective, 1),
+            ];
+            state.broke_loop = true;
+            return Ok(commands.into());
+        }
         Syntax::Unit => {}
         other => return Err(format!("Unexpected item `{other:?}`")),
     }
     Ok(VecCmd::default())
 }
 
+/// interpret the statements after one that can `break`, skipping them once it has
+fn after_break(
+    statements: &[Syntax],
+    state: &mut InterRepr,
+    path: &Path,
+    src_files: &mut BTreeSet<PathBuf>,
+    config: &Config,
+) -> SResult<VecCmd> {
+    let Some(tag) = state.for_each_tags.last().cloned() else {
+        return Err(String::from("`break` can only be used inside a `for {var} in {selector}` loop"))
+    };
+    let rest = Syntax::Array(statements.into());
+    let inner = inner_interpret(&rest, state, path, src_files, config)?;
+    Ok(Command::execute(
+        &[ExecuteOption::IfScoreMatches {
+            invert: true,
+            target: format!("%{tag}").into(),
+            objective: config.dummy_objective.clone(),
+            lower: Some(1),
+            upper: Some(1),
+        }],
+        inner,
+        &format!("__internal__/after_break_{:x}", get_hash(&rest)),
+        state,
+    )
+    .into_vec())
+}
+
 fn get_data_location(src: &Syntax) -> SResult<(VecCmd, DataLocation)> {
     if let Ok(data) = DataLocation::try_from(src.clone()) {
         return Ok((VecCmd::default(), data));
     }
-    match src {
-        other => Err(format!("Can't get data location from `{other:?}`")),
-    }
+    Err(format!("Can't get data location from `{src:?}`"))
+}
+
+/// replace an enum variant like `State::Firing` with its value
+fn resolve_enum<'a>(src: &'a Syntax, state: &InterRepr) -> SResult<Cow<'a, Syntax>> {
+    let Syntax::BinaryOp { lhs, operation: Operation::DoubleColon, rhs } = src else {
+        return Ok(Cow::Borrowed(src))
+    };
+    let (Syntax::Identifier(name), Syntax::Identifier(variant)) = (&**lhs, &**rhs) else {
+        // something like `bossbar::fight.value`
+        return Ok(Cow::Borrowed(src))
+    };
+    let Some(variants) = state.enums.get(name) else {
+        return Err(format!("Unknown enum `{name}`; enums must be declared with `@enum` before they're used"))
+    };
+    variants
+        .iter()
+        .find(|(v, _)| v == variant)
+        .map(|(_, value)| Cow::Owned(Syntax::Integer(*value)))
+        .ok_or_else(|| format!("Enum `{name}` has no variant `{variant}`"))
 }
 
 /// ## Testing Only
@@ -96,6 +169,7 @@ pub fn test_interpret(src: &Syntax) -> Vec<Command> {
         namespace: "test".into(),
         dummy_objective: "dummy".into(),
         fixed_point_accuracy: 100,
+        strict_objectives: false,
     };
     inner_interpret(
         src,
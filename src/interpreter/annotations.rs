@@ -7,8 +7,11 @@ use std::{
 use super::{get_data_location, inner_interpret};
 use crate::{lexer::tokenize, parser::parse, types::prelude::*, Config};
 
+mod custom_block;
 mod effect;
 mod item;
+mod particle;
+mod placement;
 
 macro_rules! interpret_fn {
     ($fn_buf: ident $config: ident, $value: expr, $state: expr, $path: expr, $src_files: expr) => {
@@ -37,9 +40,14 @@ pub(super) fn annotations(
     config: &Config,
 ) -> SResult<VecCmd> {
     match name {
+        "block" => custom_block::block(properties, state, path, src_files, config)?,
+        "bossbar" => super::bossbar::declaration(properties, state, config)?,
+        "clone" => return placement::clone(properties),
         "effect" => {
             return effect::effect(properties);
         }
+        "enum" => enum_declaration(properties, state)?,
+        "fill" => return placement::fill(properties),
         "function" => {
             let func = RStr::try_from(properties)
                 .map_err(|e| format!("Function annotation should have a string; {e}"))?;
@@ -63,6 +71,8 @@ pub(super) fn annotations(
             let item = item::item(properties, state, path, src_files, config)?;
             state.items.push(item);
         }
+        "objective" => objective(properties, state)?,
+        "particle" => return particle::particle(properties),
         "raw" => match properties {
             Syntax::String(cmd) => return Ok(vec![Command::Raw(cmd.clone())].into()),
             Syntax::Array(arr) => {
@@ -89,7 +99,23 @@ pub(super) fn annotations(
         "raycast" => {
             return raycast(properties, state, path, src_files, config);
         }
+        "schedule" => return schedule(properties, state, path, src_files, config),
+        "setblock" => return placement::setblock(properties),
         "sound" | "playsound" => return sound(properties),
+        "tag" => return tag(properties, state),
+        "title_times" => return title_times(properties),
+        "unschedule" => {
+            let func = RStr::try_from(properties)
+                .map_err(|_| format!("`@unschedule` takes a function name; got `{properties:?}`"))?;
+            return Ok(vec![Command::ScheduleClear(func)].into());
+        }
+        "team" => match properties {
+            Syntax::BinaryOp {
+                operation: Operation::Colon,
+                ..
+            } => super::team::declaration(properties, state, config)?,
+            _ => return super::team::membership(properties, state),
+        },
         "random" | "rand" => {
             let Syntax::BinaryOp { lhs, operation: Operation::In, rhs } = properties else {
                 return Err(format!("`@random` annotation takes `{{var}} in {{...}}`; got `{properties:?}`"))
@@ -108,6 +134,238 @@ pub(super) fn annotations(
     Ok(VecCmd::default())
 }
 
+/// declare an enum like `@enum State { Idle, Charging, Firing = 10 }`
+fn enum_declaration(properties: &Syntax, state: &mut InterRepr) -> SResult<()> {
+    let Syntax::BinaryOp { lhs, operation: Operation::Colon, rhs } = properties else {
+        return Err(format!("Expected `@enum {{name}} {{...}}`; got `@enum {properties:?}`"))
+    };
+    let Syntax::Identifier(name) = &**lhs else {
+        return Err(format!("Enum name must be an identifier; got `{lhs:?}`"))
+    };
+    if state.enums.contains_key(name) {
+        return Err(format!("Enum `{name}` is already declared"));
+    }
+    let declarations: Vec<(RStr, Option<i32>)> = match &**rhs {
+        Syntax::Array(arr) => arr
+            .iter()
+            .map(|syn| match syn {
+                Syntax::Identifier(variant) => Ok((variant.clone(), None)),
+                Syntax::BinaryOp {
+                    lhs,
+                    operation: Operation::Equal,
+                    rhs,
+                } => match (&**lhs, &**rhs) {
+                    (Syntax::Identifier(variant), Syntax::Integer(value)) => {
+                        Ok((variant.clone(), Some(*value)))
+                    }
+                    _ => Err(format!("Expected `{{variant}} = {{integer}}` in enum `{name}`; got `{syn:?}`")),
+                },
+                other => Err(format!("Expected a variant name in enum `{name}`; got `{other:?}`")),
+            })
+            .collect::<SResult<_>>()?,
+        // an object would quietly keep only the last of two variants with the same name
+        Syntax::Object(obj) if !obj.is_empty() => {
+            return Err(format!("Enum `{name}` gives its variants values with `=`, like `{{variant}} = 0`; got `{{variant}}: {{value}}`"))
+        }
+        other => return Err(format!("Enum `{name}` needs a list of variants; got `{other:?}`")),
+    };
+    let mut variants: Vec<(RStr, i32)> = Vec::new();
+    let mut next_value = 0;
+    for (variant, value) in declarations {
+        if variants.iter().any(|(v, _)| *v == variant) {
+            return Err(format!("Enum `{name}` has more than one variant named `{variant}`"));
+        }
+        let value = value.unwrap_or(next_value);
+        if let Some((other, _)) = variants.iter().find(|(_, v)| *v == value) {
+            return Err(format!("Enum `{name}` gives `{variant}` the same value as `{other}`: {value}"));
+        }
+        next_value = value + 1;
+        variants.push((variant, value));
+    }
+    state.enums.insert(name.clone(), variants);
+    Ok(())
+}
+
+/// declare a scoreboard objective like `@objective jumps { criteria: "minecraft.custom:minecraft.jump", sidebar: true }`
+fn objective(properties: &Syntax, state: &mut InterRepr) -> SResult<()> {
+    let Syntax::BinaryOp { lhs, operation: Operation::Colon, rhs } = properties else {
+        return Err(format!("Expected `@objective {{name}} {{...}}`; got `@objective {properties:?}`"))
+    };
+    let Syntax::Identifier(name) = &**lhs else {
+        return Err(format!("Objective name must be an identifier; got `{lhs:?}`"))
+    };
+    if state.objective_displays.contains_key(name) {
+        return Err(format!("Objective `{name}` is already declared"));
+    }
+    let mut criteria: RStr = "dummy".into();
+    let mut display = ObjectiveDisplay::default();
+    match &**rhs {
+        Syntax::Object(obj) => {
+            for (k, v) in obj {
+                match &**k {
+                    "criteria" | "criterion" => criteria = String::try_from(v)?.into(),
+                    "display" | "name" => {
+                        display.name = Some(match v {
+                            Syntax::String(text) => Nbt::String(text.clone()).to_json(),
+                            Syntax::Object(_) => Nbt::try_from(v)?.to_json(),
+                            other => return Err(format!("Expected a string or text component for the display name of `{name}`; got `{other:?}`")),
+                        });
+                    }
+                    "render" => match v {
+                        Syntax::Identifier(render) if matches!(&**render, "integer" | "hearts") => {
+                            display.render = Some(render.clone());
+                        }
+                        other => return Err(format!("Expected `integer` or `hearts` for the render type of `{name}`; got `{other:?}`")),
+                    },
+                    slot @ ("sidebar" | "list" | "below_name") => match v {
+                        Syntax::Identifier(b) if &**b == "true" => display.slots.push(slot.into()),
+                        Syntax::Identifier(b) if &**b == "false" => {}
+                        other => return Err(format!("Expected `true` or `false` for `{slot}`; got `{other:?}`")),
+                    },
+                    other => return Err(format!("Invalid key for objective `{name}`: `{other}`")),
+                }
+            }
+        }
+        // `@objective jumps "minecraft.custom:minecraft.jump"`
+        Syntax::String(str) => criteria = str.clone(),
+        other => return Err(format!("Expected an object or criteria for objective `{name}`; got `{other:?}`")),
+    }
+    state.objectives.insert(name.clone(), criteria);
+    state.objective_displays.insert(name.clone(), display);
+    Ok(())
+}
+
+/// add or remove tags, like `tag @s += marked` or `tag @e[type=pig] -= [a, b]`
+fn tag(properties: &Syntax, state: &mut InterRepr) -> SResult<VecCmd> {
+    let Syntax::BinaryOp { lhs, operation: operation @ (Operation::AddEq | Operation::SubEq), rhs } = properties else {
+        return Err(format!("`tag` takes `{{selector}} += {{tag}}` or `{{selector}} -= {{tag}}`; got `{properties:?}`"))
+    };
+    let Syntax::Selector(target) = &**lhs else {
+        return Err(format!("Only selectors can have tags; got `{lhs:?}`"))
+    };
+    let target = target.stringify()?;
+    let tags = match &**rhs {
+        Syntax::Array(arr) => &arr[..],
+        tag => std::slice::from_ref(tag),
+    };
+    let add = *operation == Operation::AddEq;
+    tags.iter()
+        .map(|tag| {
+            let tag: RStr = String::try_from(tag)?.into();
+            if add {
+                state.added_tags.insert(tag.clone());
+            }
+            Ok(Command::Tag {
+                target: target.clone(),
+                add,
+                tag,
+            })
+        })
+        .collect::<SResult<Vec<Command>>>()
+        .map(Into::into)
+}
+
+/// schedule a function like `@schedule my_func in 5s replace`, or a block like `@schedule 2s {...}`
+fn schedule(
+    properties: &Syntax,
+    state: &mut InterRepr,
+    path: &Path,
+    src_files: &mut BTreeSet<PathBuf>,
+    config: &Config,
+) -> SResult<VecCmd> {
+    let Syntax::Array(arr) = properties else {
+        unreachable!("`@schedule` is always parsed into an array")
+    };
+    let [target, duration, mode] = &arr[..] else {
+        unreachable!("`@schedule` is always parsed into 3 parts")
+    };
+    let time = ticks(duration)?;
+    let func: RStr = match target {
+        Syntax::String(func) => func.clone(),
+        body @ (Syntax::Array(_) | Syntax::Object(_)) => {
+            let func: RStr = format!("__internal__/schedule_{:x}", get_hash(body)).into();
+            let inner = inner_interpret(body, state, path, src_files, config)?;
+            state.functions.insert(func.clone(), inner);
+            func
+        }
+        other => return Err(format!("Expected a function or block to schedule; got `{other:?}`")),
+    };
+    Ok(vec![Command::Schedule {
+        func,
+        time,
+        // vanilla replaces by default
+        replace: !matches!(mode, Syntax::Identifier(mode) if &**mode == "append"),
+    }]
+    .into())
+}
+
+/// convert a time like `5s`, `20t`, `1d`, or `1.5s` to ticks
+pub(super) fn ticks(duration: &Syntax) -> SResult<i32> {
+    let ticks = match duration {
+        Syntax::Integer(ticks) => *ticks,
+        Syntax::Identifier(duration) => {
+            let (amount, per_unit) = match duration.split_at(duration.len() - 1) {
+                (amount, "t") => (amount, 1.0),
+                (amount, "s") => (amount, 20.0),
+                (amount, "d") => (amount, 24000.0),
+                _ => return Err(format!("Unknown time unit in `{duration}`; expected `t`, `s`, or `d`")),
+            };
+            let Ok(amount) = amount.parse::<f64>() else {
+                return Err(format!("Invalid time `{duration}`"))
+            };
+            let ticks = amount * per_unit;
+            if ticks.fract() != 0.0 || ticks > f64::from(i32::MAX) {
+                return Err(format!("`{duration}` isn't a whole number of ticks"));
+            }
+            #[allow(clippy::cast_possible_truncation)]
+            let ticks = ticks as i32;
+            ticks
+        }
+        other => return Err(format!("Expected a time like `5s`, `20t`, or `1d`; got `{other:?}`")),
+    };
+    if ticks <= 0 {
+        return Err(format!("Can't schedule something {ticks} ticks from now; the time must be at least 1 tick"));
+    }
+    Ok(ticks)
+}
+
+/// set how long titles are shown, like `@title_times { fade_in: 10, stay: 70, fade_out: 20 }`
+fn title_times(properties: &Syntax) -> SResult<VecCmd> {
+    let Syntax::Object(obj) = properties else {
+        return Err(format!("Title times annotation expects an object, not {properties:?}"))
+    };
+    // vanilla defaults
+    let mut fade_in = 10;
+    let mut stay = 70;
+    let mut fade_out = 20;
+    let mut target: Selector<Syntax> = Selector::a();
+    for (k, v) in obj {
+        match (&**k, v) {
+            ("fade_in" | "fadein", Syntax::Integer(int)) => fade_in = *int,
+            ("stay", Syntax::Integer(int)) => stay = *int,
+            ("fade_out" | "fadeout", Syntax::Integer(int)) => fade_out = *int,
+            ("target", Syntax::Selector(selector)) => target = selector.clone(),
+            ("fade_in" | "fadein" | "stay" | "fade_out" | "fadeout", other) => {
+                return Err(format!("Expected a number of ticks for `{k}`; got `{other:?}`"))
+            }
+            ("target", other) => {
+                return Err(format!("Expected a selector for title times target; got `{other:?}`"))
+            }
+            (other, _) => return Err(format!("Invalid key for title times annotation: `{other}`")),
+        }
+    }
+    if fade_in < 0 || stay < 0 || fade_out < 0 {
+        return Err(String::from("Title times can't be negative"));
+    }
+    Ok(vec![Command::TitleTimes {
+        target: target.stringify()?,
+        fade_in,
+        stay,
+        fade_out,
+    }]
+    .into())
+}
+
 fn sound(properties: &Syntax) -> SResult<VecCmd> {
     let Syntax::Object(obj) = properties else {
         return Err(format!("Sound annotation expects an object, not {properties:?}"))
@@ -222,7 +480,7 @@ fn raycast(
 
     if callback.is_empty() {
         return Err(String::from("Raycast requires a hit/callback function"));
-    };
+    }
 
     let hash: RStr = format!("{hash:x}").into();
     let score_name: RStr = format!("%__timer__{hash}").into();
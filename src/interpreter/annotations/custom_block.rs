@@ -0,0 +1,181 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
+
+use super::item::{handler, item};
+use crate::{types::prelude::*, Config};
+
+/// items that place an entity, which is given a tag saying which block it places
+const PLACERS: [&str; 3] = ["item_frame", "glow_item_frame", "armor_stand"];
+
+/// vanilla blocks a custom block can use, with what they drop if it isn't themselves or nothing;
+/// the drop is swapped for the custom block's item, so it has to be known ahead of time
+const VANILLA_BLOCKS: [(&str, Option<&str>); 61] = [
+    ("spawner", None),
+    ("barrier", None),
+    ("bedrock", None),
+    ("budding_amethyst", None),
+    ("glass", None),
+    ("tinted_glass", None),
+    ("dirt", None),
+    ("cobblestone", None),
+    ("smooth_stone", None),
+    ("stone_bricks", None),
+    ("mossy_stone_bricks", None),
+    ("deepslate_bricks", None),
+    ("deepslate_tiles", None),
+    ("polished_deepslate", None),
+    ("polished_andesite", None),
+    ("polished_diorite", None),
+    ("polished_granite", None),
+    ("polished_blackstone", None),
+    ("bricks", None),
+    ("mud_bricks", None),
+    ("nether_bricks", None),
+    ("end_stone", None),
+    ("end_stone_bricks", None),
+    ("purpur_block", None),
+    ("quartz_block", None),
+    ("terracotta", None),
+    ("obsidian", None),
+    ("crying_obsidian", None),
+    ("iron_block", None),
+    ("gold_block", None),
+    ("diamond_block", None),
+    ("emerald_block", None),
+    ("netherite_block", None),
+    ("copper_block", None),
+    ("amethyst_block", None),
+    ("ancient_debris", None),
+    ("hay_block", None),
+    ("bone_block", None),
+    ("magma_block", None),
+    ("crafting_table", None),
+    ("smithing_table", None),
+    ("fletching_table", None),
+    ("cartography_table", None),
+    ("lodestone", None),
+    ("target", None),
+    ("note_block", None),
+    ("stone", Some("cobblestone")),
+    ("deepslate", Some("cobbled_deepslate")),
+    ("grass_block", Some("dirt")),
+    ("glowstone", Some("glowstone_dust")),
+    ("sea_lantern", Some("prismarine_crystals")),
+    ("bookshelf", Some("book")),
+    ("clay", Some("clay_ball")),
+    ("coal_ore", Some("coal")),
+    ("iron_ore", Some("raw_iron")),
+    ("gold_ore", Some("raw_gold")),
+    ("copper_ore", Some("raw_copper")),
+    ("diamond_ore", Some("diamond")),
+    ("emerald_ore", Some("emerald")),
+    ("lapis_ore", Some("lapis_lazuli")),
+    ("redstone_ore", Some("redstone")),
+];
+
+/// families of vanilla blocks that all drop themselves, like `oak_planks` or `red_wool`
+const SELF_DROPPING: [&str; 6] = ["_planks", "_log", "_wood", "_wool", "_concrete", "_terracotta"];
+
+/// interpret a custom block like `@block { name: "Grinder", model: "grinder", on_interact: ... }`
+///
+/// the block is placed with an item, so any other item properties like `lore` or `recipe` go to that item
+pub(super) fn block(
+    src: &Syntax,
+    state: &mut InterRepr,
+    path: &Path,
+    src_files: &mut BTreeSet<PathBuf>,
+    config: &Config,
+) -> SResult<()> {
+    let Syntax::Object(src) = src else {
+        return Err(format!("Expected an object for block annotation; got `{src:?}`"))
+    };
+    let mut block = Block::default();
+    let mut item_properties = src.clone();
+    for (prop, value) in src {
+        match prop.as_ref() {
+            "name" => {
+                let Ok(name) = RStr::try_from(value) else {
+                    return Err(String::from("Block name must be a string"))
+                };
+                block.name = name;
+                continue;
+            }
+            "base_item" => {
+                let Ok(base) = RStr::try_from(value) else {
+                    return Err(String::from("Block base item must be a string"))
+                };
+                item_properties.insert("base".into(), Syntax::String(base));
+            }
+            "model" => {
+                item_properties.insert("custom_model".into(), value.clone());
+            }
+            "block" => {
+                let Ok(vanilla) = RStr::try_from(value) else {
+                    return Err(format!("Block `block` must be a block name; got `{value:?}`"))
+                };
+                block.vanilla = namespaced(&vanilla, "minecraft").into();
+                block.vanilla_drop = vanilla_drop(&block.vanilla)?;
+            }
+            "on_place" => block.on_place.extend(handler(value, state, path, src_files, config)?),
+            "on_break" => block.on_break.extend(handler(value, state, path, src_files, config)?),
+            "on_interact" => block.on_interact.extend(handler(value, state, path, src_files, config)?),
+            "tick" => block.tick.extend(handler(value, state, path, src_files, config)?),
+            // everything else describes the item
+            _ => continue,
+        }
+        item_properties.remove(prop);
+    }
+    if block.name.is_empty() {
+        return Err(String::from(
+            "Block must have a specified name: @block {... name: \"Grinder\"}",
+        ));
+    }
+    item_properties
+        .entry("base".into())
+        .or_insert_with(|| Syntax::String("item_frame".into()));
+    let mut item = item(&Syntax::Object(item_properties), state, path, src_files, config)?;
+    if !PLACERS.contains(&&*item.base) {
+        return Err(format!(
+            "Block base item must place an entity; expected item_frame, glow_item_frame, or armor_stand; got `{}`",
+            item.base
+        ));
+    }
+    // the placed entity is tagged so the tick function can swap it for the block
+    let Nbt::Object(ref mut obj) = item.nbt else {
+        return Err(format!("Item nbt should be an object; got `{}`", item.nbt))
+    };
+    obj.insert(
+        "EntityTag".into(),
+        nbt!({
+            id: format!("minecraft:{}", item.base),
+            Tags: nbt!([format!("place_{}", fmt_mc_ident(&block.name))]),
+            Invisible: true
+        }),
+    );
+    // counts the players that mined the block, so it only drops its item for them
+    let vanilla = block.vanilla.split_once('[').map_or(&*block.vanilla, |(id, _)| id);
+    state.objectives.insert(
+        format!("mined_{}", fmt_mc_ident(&block.name)).into(),
+        format!("minecraft.mined:{}", vanilla.replace(':', ".")).into(),
+    );
+    state.items.push(item);
+    state.blocks.push(block);
+    Ok(())
+}
+
+/// what a vanilla block like `minecraft:stone` drops, if it isn't itself or nothing
+fn vanilla_drop(vanilla: &str) -> SResult<Option<RStr>> {
+    let id = vanilla.split_once('[').map_or(vanilla, |(id, _)| id);
+    let id = id.strip_prefix("minecraft:").unwrap_or(id);
+    if let Some((_, drop)) = VANILLA_BLOCKS.iter().find(|(block, _)| *block == id) {
+        return Ok(drop.map(|drop| format!("minecraft:{drop}").into()));
+    }
+    if SELF_DROPPING.iter().any(|family| id.ends_with(family)) {
+        return Ok(None);
+    }
+    Err(format!(
+        "Block `block` must be a vanilla block with a known drop, like spawner, stone, or oak_planks; got `{vanilla}`"
+    ))
+}
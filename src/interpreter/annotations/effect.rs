@@ -8,7 +8,7 @@ pub(super) fn effect(src: &Syntax) -> SResult<VecCmd> {
     let mut duration = None;
     let mut level = 1;
     if let Syntax::Object(src) = src {
-        for (prop, value) in src.iter() {
+        for (prop, value) in src {
             match prop.as_ref() {
                 "selector" | "target" => {
                     let Syntax::Selector(sel) = &value else {
@@ -56,7 +56,7 @@ pub(super) fn effect(src: &Syntax) -> SResult<VecCmd> {
         effect = Some(str);
     } else {
         return Err(format!("Expected an object for item annotation; got `{src:?}`"));
-    };
+    }
 
     let Some(effect) = effect else {
         return Err(String::from("Effect must include the effect id; {... effect: \"...\"}"))
@@ -5,7 +5,15 @@ use std::{
 
 use lazy_regex::lazy_regex;
 
-use crate::{interpreter::inner_interpret, types::prelude::*, Config};
+use crate::{
+    interpreter::{
+        attribute::{self, MODIFIER_ID_VERSION, UNPREFIXED_VERSION},
+        inner_interpret,
+        selector_block::tellraw_component,
+    },
+    types::prelude::*,
+    Config,
+};
 
 #[allow(clippy::too_many_lines)]
 pub(super) fn item(
@@ -21,7 +29,8 @@ pub(super) fn item(
     let mut item = Item::default();
     let mut recipe_buf = Vec::new();
     let mut custom_model_path = None;
-    for (prop, value) in src.iter() {
+    let mut properties = Properties::default();
+    for (prop, value) in src {
         match prop.as_ref() {
             "name" => {
                 let Ok(name) = RStr::try_from(value) else {
@@ -41,54 +50,29 @@ pub(super) fn item(
                 };
                 item.nbt = obj;
             }
-            "on_consume" => match value {
-                Syntax::String(str) => item.on_consume.push(Command::Function(str.clone()).into()),
-                Syntax::Block(BlockType::Function, name, body) => {
-                    let (Syntax::Identifier(name) | Syntax::String(name)) = &**name else {
-                        item.on_consume.extend(inner_interpret(value, state, path, src_files, config)?);
-                        continue;
-                    };
-                    let new_body = inner_interpret(body, state, path, src_files, config)?;
-                    state.functions.insert(name.clone(), new_body);
-                    item.on_consume.push(Command::Function(name.clone()).into());
-                }
-                other => {
-                    item.on_consume
-                        .extend(inner_interpret(other, state, path, src_files, config)?);
-                }
-            },
-            "on_use" => match value {
-                Syntax::String(str) => item.on_use.push(Command::Function(str.clone()).into()),
-                Syntax::Block(BlockType::Function, name, body) => {
-                    let (Syntax::Identifier(name) | Syntax::String(name)) = &**name else {
-                        item.on_use.extend(inner_interpret(value, state, path, src_files, config)?);
-                        continue;
-                    };
-                    let new_body = inner_interpret(body, state, path, src_files, config)?;
-                    state.functions.insert(name.clone(), new_body);
-                    item.on_use.push(Command::Function(name.clone()).into());
-                }
-                other => item
-                    .on_use
-                    .extend(inner_interpret(other, state, path, src_files, config)?),
-            },
-            "while_using" => match value {
-                Syntax::String(str) => item.while_using.push(Command::Function(str.clone()).into()),
-                Syntax::Block(BlockType::Function, name, body) => {
-                    let (Syntax::Identifier(name) | Syntax::String(name)) = &**name else {
-                        item.while_using.extend(inner_interpret(value, state, path, src_files, config)?);
-                        continue;
-                    };
-                    let new_body = inner_interpret(body, state, path, src_files, config)?;
-                    state.functions.insert(name.clone(), new_body);
-                    item.while_using
-                        .push(Command::Function(name.clone()).into());
-                }
-                other => {
-                    item.while_using
-                        .extend(inner_interpret(other, state, path, src_files, config)?);
-                }
+            "on_consume" => item.on_consume.extend(handler(value, state, path, src_files, config)?),
+            "on_use" => item.on_use.extend(handler(value, state, path, src_files, config)?),
+            "while_using" => item.while_using.extend(handler(value, state, path, src_files, config)?),
+            "cooldown" => item.cooldown = super::ticks(value)?,
+            "on_cooldown" => item.on_cooldown.extend(handler(value, state, path, src_files, config)?),
+            "show_cooldown" => item.show_cooldown = boolean(value, prop)?,
+            "on_drop" => item.on_drop.extend(handler(value, state, path, src_files, config)?),
+            "on_pickup" => item.on_pickup.extend(handler(value, state, path, src_files, config)?),
+            "durability" => match value {
+                Syntax::Integer(uses) if *uses > 0 => item.durability = *uses,
+                other => return Err(format!("Item durability must be a positive integer; got `{other:?}`")),
             },
+            "break_sound" => {
+                let Ok(sound) = RStr::try_from(value) else {
+                    return Err(format!("Item break sound must be a sound name; got `{value:?}`"))
+                };
+                item.break_sound = namespaced(&sound, "minecraft").into();
+            }
+            "on_break" => item.on_break.extend(handler(value, state, path, src_files, config)?),
+            "on_hit" => item.on_hit.extend(handler(value, state, path, src_files, config)?),
+            "on_kill" => item.on_kill.extend(handler(value, state, path, src_files, config)?),
+            "on_hurt" => item.on_hurt.extend(handler(value, state, path, src_files, config)?),
+            "on_block_break" => item.on_block_break.extend(handler(value, state, path, src_files, config)?),
             "recipe" => {
                 if let Syntax::Array(arr) = value {
                     recipe_buf.extend(arr.iter().map(recipe).collect::<SResult<Vec<_>>>()?);
@@ -100,74 +84,140 @@ pub(super) fn item(
                 let Syntax::Object(obj) = value else {
                     return Err(format!("Expected an object for `while_slot` property; got `{value:?}`"))
                 };
-                for (slot_key, slot_value) in obj.iter() {
-                    // SLOT INFO
-                    // 9  10 11 12 13 14 15 16 17
-                    // 18 19 20 21 22 23 24 25 26
-                    // 27 28 29 30 31 12 33 34 35
-                    // 0  1  2  3  4  5  6  7  8
-                    // -106
-                    let slot = if let Some(captures) =
-                        lazy_regex!("^slot_(?P<slot>[0-9]+)$").captures(slot_key)
-                    {
-                        // given the regex above, `captures.name` can never fail
-                        captures
-                            .name("slot")
-                            .unwrap()
-                            .as_str()
-                            .parse()
-                            .map_err(|err| {
-                                format!("While checking if item is in {slot_key}: {err}")
-                            })?
-                    } else if let Some(captures) =
-                        lazy_regex!("^hotbar_(?P<slot>[0-8])$").captures(slot_key)
-                    {
-                        // given the regex above, both `captures.name` and `parse::<i32>` can never fail
-                        captures
-                            .name("slot")
-                            .unwrap()
-                            .as_str()
-                            .parse::<i8>()
-                            .unwrap()
-                    } else {
-                        match &**slot_key {
-                            "offhand" => -106,
-                            "head" => 103,
-                            "chest" => 102,
-                            "legs" => 101,
-                            "feet" => 100,
-                            _ => return Err(format!("Unexpected slot: `{slot_key}`")),
-                        }
-                    };
+                for (slot_key, slot_value) in obj {
+                    let slot = slot_number(slot_key)?;
                     item.slot_checks.push((
                         slot,
                         inner_interpret(slot_value, state, path, src_files, config)?,
                     ));
                 }
             }
+            "on_equip" | "on_unequip" => {
+                let Syntax::Object(obj) = value else {
+                    return Err(format!("Expected an object of slots for `{prop}` property; got `{value:?}`"))
+                };
+                for (slot_key, handler_value) in obj {
+                    let slot = match &**slot_key {
+                        "mainhand" | "selected" => None,
+                        other => Some(slot_number(other)?),
+                    };
+                    let handler = handler(handler_value, state, path, src_files, config)?;
+                    let (_, on_equip, on_unequip) = item
+                        .equip_events
+                        .entry(slot_key.clone())
+                        .or_insert_with(|| (slot, VecCmd::default(), VecCmd::default()));
+                    if &**prop == "on_equip" {
+                        on_equip.extend(handler);
+                    } else {
+                        on_unequip.extend(handler);
+                    }
+                }
+            }
             "custom_model" => {
                 custom_model_path = Some(String::try_from(value).map_err(|err| {
                     format!("`custom_model` value should be a path to a file; {err}")
                 })?);
             }
+            "lore" => {
+                let Syntax::Array(lines) = value else {
+                    return Err(format!("Item lore must be a list of text; got `{value:?}`"))
+                };
+                properties.lore = lines
+                    .iter()
+                    .map(|line| tellraw_component(line, config))
+                    .collect::<SResult<_>>()?;
+            }
+            "enchantments" => {
+                let Syntax::Object(obj) = value else {
+                    return Err(format!("Item enchantments must be an object like `{{sharpness: 5}}`; got `{value:?}`"))
+                };
+                for (id, level) in obj {
+                    let Syntax::Integer(level) = level else {
+                        return Err(format!("Enchantment `{id}` needs an integer level; got `{level:?}`"))
+                    };
+                    properties.enchantments.push((id.clone(), *level));
+                }
+            }
+            "attributes" => {
+                let Syntax::Array(arr) = value else {
+                    return Err(format!("Item attributes must be a list of modifiers; got `{value:?}`"))
+                };
+                properties.attributes = arr.iter().map(modifier).collect::<SResult<_>>()?;
+            }
+            "unbreakable" => properties.unbreakable = boolean(value, prop)?,
+            "glint" => properties.glint = Some(boolean(value, prop)?),
+            "hide" => {
+                let Syntax::Array(arr) = value else {
+                    return Err(format!("Item `hide` must be a list like `[enchantments, attributes]`; got `{value:?}`"))
+                };
+                for hidden in arr.iter() {
+                    match hidden {
+                        Syntax::Identifier(name) if HIDE_FLAGS.iter().any(|(flag, _)| flag == &&**name) => {
+                            properties.hide.insert(name.clone());
+                        }
+                        other => return Err(format!("Can't hide `{other:?}`; expected one of {}", HIDE_FLAGS.map(|(flag, _)| flag).join(", "))),
+                    }
+                }
+            }
             other => return Err(format!("Unexpected item property: `{other}`")),
         }
     }
+    if item.durability > 0 {
+        durability(&mut item, &mut properties, state)?;
+    } else if !item.on_break.is_empty() {
+        return Err(format!("Item `{}` needs a `durability` to break", item.name));
+    }
+    item.properties = properties.versioned(config);
     if !item.on_use.is_empty() {
         state.objectives.insert(
             format!("use_{}", item.base).into(),
             format!("minecraft.used:minecraft.{}", item.base).into(),
         );
     }
+    if item.cooldown > 0 {
+        if item.on_use.is_empty() {
+            return Err(format!("Item `{}` has a cooldown but no `on_use` for it to limit", item.name));
+        }
+        state.objectives.insert(
+            format!("cd_{}", fmt_mc_ident(&item.name)).into(),
+            "dummy".into(),
+        );
+    } else if !item.on_cooldown.is_empty() || item.show_cooldown {
+        return Err(format!("Item `{}` needs a `cooldown` for `on_cooldown` or `show_cooldown`", item.name));
+    }
+    // remembers which entities the holder just hit
+    if !item.on_hit.is_empty() {
+        state.objectives.insert(format!("hit_{}", fmt_mc_ident(&item.name)).into(), "dummy".into());
+    }
+    // tools count as used whenever they break a block, and when they hit something, which the compiler filters out
+    if !item.on_block_break.is_empty() {
+        state.objectives.insert(
+            format!("mine_{}", item.base).into(),
+            format!("minecraft.used:minecraft.{}", item.base).into(),
+        );
+    }
+    if !item.on_drop.is_empty() {
+        state.objectives.insert(
+            format!("drop_{}", item.base).into(),
+            format!("minecraft.dropped:minecraft.{}", item.base).into(),
+        );
+    }
+    if !item.on_pickup.is_empty() {
+        state.objectives.insert(
+            format!("pickup_{}", item.base).into(),
+            format!("minecraft.picked_up:minecraft.{}", item.base).into(),
+        );
+        state.objectives.insert(format!("has_{}", fmt_mc_ident(&item.name)).into(), "dummy".into());
+    }
     if let [recipe] = &recipe_buf[..] {
         state
             .recipes
-            .insert(item.name.clone(), (recipe.to_json(), item.name.clone()));
+            .insert(item.name.clone(), (recipe.clone(), item.name.clone()));
     } else {
         for recipe in recipe_buf {
             state.recipes.insert(
                 format!("{}_{:x}", item.name, get_hash(&recipe)).into(),
-                (recipe.to_json(), item.name.clone()),
+                (recipe, item.name.clone()),
             );
         }
     }
@@ -205,6 +255,333 @@ pub(super) fn item(
     }
 }
 
+/// count down the item's uses whenever it's used or hits something, showing them in the lore
+fn durability(item: &mut Item, properties: &mut Properties, state: &mut InterRepr) -> SResult<()> {
+    if item.on_use.is_empty() && item.on_hit.is_empty() {
+        return Err(format!("Item `{}` has durability but no `on_use` or `on_hit` to use it up", item.name));
+    }
+    let ident = fmt_mc_ident(&item.name);
+    item.lore.clone_from(&properties.lore);
+    properties.lore.push(nbt!({
+        text: format!("Durability: {0} / {0}", item.durability),
+        color: "gray",
+        italic: false
+    }));
+    let use_up = Command::Function(format!("durability/{ident}").into());
+    for trigger in [&mut item.on_use, &mut item.on_hit] {
+        if !trigger.is_empty() {
+            trigger.extend(VecCmd::from(vec![use_up.clone()]));
+        }
+    }
+    state
+        .objectives
+        .insert(format!("dur_{ident}").into(), "dummy".into());
+    Ok(())
+}
+
+/// interpret an item trigger like `on_use`, which can be a function name, a named function, or a body
+pub(super) fn handler(
+    value: &Syntax,
+    state: &mut InterRepr,
+    path: &Path,
+    src_files: &mut BTreeSet<PathBuf>,
+    config: &Config,
+) -> SResult<VecCmd> {
+    match value {
+        Syntax::String(str) => Ok(vec![Command::Function(str.clone())].into()),
+        Syntax::Block(BlockType::Function, name, body) => {
+            let (Syntax::Identifier(name) | Syntax::String(name)) = &**name else {
+                return inner_interpret(value, state, path, src_files, config);
+            };
+            let new_body = inner_interpret(body, state, path, src_files, config)?;
+            state.functions.insert(name.clone(), new_body);
+            Ok(vec![Command::Function(name.clone())].into())
+        }
+        other => inner_interpret(other, state, path, src_files, config),
+    }
+}
+
+/// the parts of the tooltip that `hide` can hide, and their bit in the old `HideFlags`
+const HIDE_FLAGS: [(&str, i32); 4] = [
+    ("enchantments", 1),
+    ("attributes", 2),
+    ("unbreakable", 4),
+    ("additional", 32),
+];
+
+/// the typed item properties, like `lore: [...]` and `enchantments: {sharpness: 5}`
+#[derive(Default)]
+struct Properties {
+    /// each line as a text component
+    lore: Vec<Nbt>,
+    enchantments: Vec<(RStr, i32)>,
+    attributes: Vec<Modifier>,
+    unbreakable: bool,
+    glint: Option<bool>,
+    hide: BTreeSet<RStr>,
+}
+
+/// an attribute modifier like `{attribute: "generic.attack_damage", amount: 4, slot: mainhand}`
+struct Modifier {
+    attribute: RStr,
+    name: RStr,
+    amount: f32,
+    /// the operation's old number and new name, like `(1, "add_multiplied_base")`
+    operation: (i32, &'static str),
+    slot: Option<RStr>,
+}
+
+impl Properties {
+    /// get the properties as tag NBT, and as item components for the versions that use them
+    fn versioned(&self, config: &Config) -> Versioned<BTreeMap<RStr, Nbt>> {
+        let mut tag = BTreeMap::new();
+        if !self.lore.is_empty() {
+            tag.insert(
+                "display".into(),
+                nbt!({ Lore: self.lore.iter().map(json_string).collect::<Vec<_>>() }),
+            );
+        }
+        if !self.enchantments.is_empty() {
+            tag.insert(
+                "Enchantments".into(),
+                Nbt::Array(
+                    self.enchantments
+                        .iter()
+                        .map(|(id, level)| nbt!({id: namespaced(id, "minecraft"), lvl: *level}))
+                        .collect(),
+                ),
+            );
+        } else if self.glint == Some(true) {
+            // an empty enchantment shows the glint without doing anything
+            tag.insert("Enchantments".into(), nbt!([nbt!({})]));
+        }
+        if self.glint == Some(false) {
+            println!("\x1b[33mWARN\x1b[0m\tItems can only have their glint removed from pack format {ITEM_COMPONENT_VERSION} on");
+        }
+        if !self.attributes.is_empty() {
+            tag.insert(
+                "AttributeModifiers".into(),
+                Nbt::Array(
+                    self.attributes
+                        .iter()
+                        .map(|modifier| {
+                            let mut nbt = BTreeMap::from([
+                                ("AttributeName".into(), Nbt::from(&modifier.attribute)),
+                                ("Name".into(), Nbt::from(&modifier.name)),
+                                ("Amount".into(), Nbt::from(modifier.amount)),
+                                ("Operation".into(), Nbt::from(modifier.operation.0)),
+                                ("UUID".into(), Nbt::IntArray(attribute::uuid_ints(&modifier.name, config).to_vec())),
+                            ]);
+                            if let Some(slot) = &modifier.slot {
+                                nbt.insert("Slot".into(), slot.into());
+                            }
+                            Nbt::Object(nbt)
+                        })
+                        .collect(),
+                ),
+            );
+        }
+        if self.unbreakable {
+            tag.insert("Unbreakable".into(), Nbt::Byte(1));
+        }
+        let hide_flags: i32 = HIDE_FLAGS
+            .iter()
+            .filter(|(flag, _)| self.hide.contains(*flag))
+            .map(|(_, bit)| bit)
+            .sum();
+        if hide_flags != 0 {
+            tag.insert("HideFlags".into(), hide_flags.into());
+        }
+        let mut versioned = Versioned::from(tag);
+        versioned.add_version(ITEM_COMPONENT_VERSION, self.components(ITEM_COMPONENT_VERSION, config));
+        // attribute modifiers changed how they're written after components were added
+        if !self.attributes.is_empty() {
+            versioned.add_version(MODIFIER_ID_VERSION, self.components(MODIFIER_ID_VERSION, config));
+            versioned.add_version(UNPREFIXED_VERSION, self.components(UNPREFIXED_VERSION, config));
+        }
+        versioned
+    }
+
+    fn components(&self, version: u8, config: &Config) -> BTreeMap<RStr, Nbt> {
+        let mut components = BTreeMap::new();
+        // hidden parts of the tooltip are hidden on their own component
+        let with_tooltip = |mut nbt: BTreeMap<RStr, Nbt>, flag: &str| {
+            if self.hide.contains(flag) {
+                nbt.insert("show_in_tooltip".into(), false.into());
+            }
+            Nbt::Object(nbt)
+        };
+        if !self.lore.is_empty() {
+            components.insert(
+                "lore".into(),
+                Nbt::Array(self.lore.iter().map(json_string).collect()),
+            );
+        }
+        if !self.enchantments.is_empty() || self.hide.contains("enchantments") {
+            let levels = self
+                .enchantments
+                .iter()
+                .map(|(id, level)| (quoted_key(id), Nbt::from(*level)))
+                .collect::<BTreeMap<_, _>>();
+            components.insert(
+                "enchantments".into(),
+                with_tooltip(BTreeMap::from([("levels".into(), Nbt::Object(levels))]), "enchantments"),
+            );
+        }
+        if let Some(glint) = self.glint {
+            components.insert("enchantment_glint_override".into(), glint.into());
+        }
+        if !self.attributes.is_empty() || self.hide.contains("attributes") {
+            let modifiers = self
+                .attributes
+                .iter()
+                .map(|modifier| {
+                    let mut nbt = BTreeMap::from([
+                        ("amount".into(), Nbt::from(modifier.amount)),
+                        ("operation".into(), Nbt::from(modifier.operation.1)),
+                        (
+                            "slot".into(),
+                            Nbt::from(modifier.slot.clone().unwrap_or_else(|| "any".into())),
+                        ),
+                    ]);
+                    if version >= UNPREFIXED_VERSION {
+                        nbt.insert("type".into(), attribute::unprefixed(&modifier.attribute).into());
+                    } else {
+                        nbt.insert("type".into(), Nbt::from(&modifier.attribute));
+                    }
+                    if version >= MODIFIER_ID_VERSION {
+                        nbt.insert("id".into(), namespaced(&modifier.name, &config.namespace).into());
+                    } else {
+                        nbt.insert("name".into(), Nbt::from(&modifier.name));
+                        nbt.insert("uuid".into(), Nbt::IntArray(attribute::uuid_ints(&modifier.name, config).to_vec()));
+                    }
+                    Nbt::Object(nbt)
+                })
+                .collect::<Vec<_>>();
+            components.insert(
+                "attribute_modifiers".into(),
+                with_tooltip(BTreeMap::from([("modifiers".into(), Nbt::Array(modifiers))]), "attributes"),
+            );
+        }
+        if self.unbreakable {
+            components.insert("unbreakable".into(), with_tooltip(BTreeMap::new(), "unbreakable"));
+        }
+        if self.hide.contains("additional") {
+            components.insert("hide_additional_tooltip".into(), nbt!({}));
+        }
+        components
+    }
+}
+
+/// interpret an attribute modifier like `{attribute: "generic.attack_damage", amount: 4, operation: add, slot: mainhand}`
+fn modifier(src: &Syntax) -> SResult<Modifier> {
+    let Syntax::Object(obj) = src else {
+        return Err(format!("Expected an attribute modifier object; got `{src:?}`"))
+    };
+    let mut attribute = None;
+    let mut name = None;
+    let mut amount = None;
+    let mut operation = (0, "add_value");
+    let mut slot = None;
+    for (k, v) in obj {
+        match (&**k, v) {
+            ("attribute" | "type", Syntax::String(a) | Syntax::Identifier(a)) => attribute = Some(a.clone()),
+            ("name" | "id", Syntax::String(n) | Syntax::Identifier(n)) => name = Some(n.clone()),
+            #[allow(clippy::cast_precision_loss)]
+            ("amount", Syntax::Integer(int)) => amount = Some(*int as f32),
+            ("amount", Syntax::Float(float)) => amount = Some(*float),
+            ("operation", Syntax::Identifier(o)) => {
+                operation = match &**o {
+                    "add" | "add_value" => (0, "add_value"),
+                    "multiply_base" | "add_multiplied_base" => (1, "add_multiplied_base"),
+                    "multiply" | "add_multiplied_total" => (2, "add_multiplied_total"),
+                    _ => return Err(format!("Unknown attribute modifier operation `{o}`; expected add, multiply_base, or multiply")),
+                }
+            }
+            ("slot", Syntax::Identifier(s)) => {
+                if !matches!(&**s, "mainhand" | "offhand" | "head" | "chest" | "legs" | "feet") {
+                    return Err(format!("Unknown attribute modifier slot `{s}`"));
+                }
+                slot = Some(s.clone());
+            }
+            _ => return Err(format!("Invalid attribute modifier property `{k}: {v:?}`")),
+        }
+    }
+    let (Some(attribute), Some(amount)) = (attribute, amount) else {
+        return Err(format!("Attribute modifiers need an attribute and amount; {{attribute: \"generic.armor\", amount: 4}}; got `{src:?}`"))
+    };
+    // modifiers need a name to identify them, so make one up if it isn't given
+    let name = name.unwrap_or_else(|| format!("{}_{:x}", attribute.replace('.', "_"), get_hash(&(&attribute, amount.to_bits(), &slot))).into());
+    Ok(Modifier {
+        attribute,
+        name,
+        amount,
+        operation,
+        slot,
+    })
+}
+
+fn boolean(src: &Syntax, prop: &str) -> SResult<bool> {
+    match src {
+        Syntax::Identifier(bool) if matches!(&**bool, "true" | "false") => Ok(&**bool == "true"),
+        other => Err(format!("Expected true or false for item `{prop}`; got `{other:?}`")),
+    }
+}
+
+/// a text component as a string of JSON, the way lore and names are stored in NBT
+fn json_string(component: &Nbt) -> Nbt {
+    component
+        .to_json()
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .into()
+}
+
+/// an NBT key that's quoted if it has characters that can't be in a bare key, like `minecraft:sharpness`
+fn quoted_key(key: &str) -> RStr {
+    if key.chars().all(|c| c.is_ascii_alphanumeric() || "_-.+".contains(c)) {
+        key.into()
+    } else {
+        format!("\"{key}\"").into()
+    }
+}
+
+/// get the inventory slot number from a name like `slot_20`, `hotbar_3`, or `offhand`
+fn slot_number(slot_key: &str) -> SResult<i8> {
+    // SLOT INFO
+    // 9  10 11 12 13 14 15 16 17
+    // 18 19 20 21 22 23 24 25 26
+    // 27 28 29 30 31 12 33 34 35
+    // 0  1  2  3  4  5  6  7  8
+    // -106
+    if let Some(captures) = lazy_regex!("^slot_(?P<slot>[0-9]+)$").captures(slot_key) {
+        // given the regex above, `captures.name` can never fail
+        return captures
+            .name("slot")
+            .unwrap()
+            .as_str()
+            .parse()
+            .map_err(|err| format!("While checking if item is in {slot_key}: {err}"));
+    }
+    if let Some(captures) = lazy_regex!("^hotbar_(?P<slot>[0-8])$").captures(slot_key) {
+        // given the regex above, both `captures.name` and `parse::<i32>` can never fail
+        return Ok(captures
+            .name("slot")
+            .unwrap()
+            .as_str()
+            .parse::<i8>()
+            .unwrap());
+    }
+    match slot_key {
+        "offhand" => Ok(-106),
+        "head" => Ok(103),
+        "chest" => Ok(102),
+        "legs" => Ok(101),
+        "feet" => Ok(100),
+        _ => Err(format!("Unexpected slot: `{slot_key}`")),
+    }
+}
+
 // given a syntax element within the item annotation, crate the nbt contents of the recipe file
 fn recipe(value: &Syntax) -> SResult<Nbt> {
     match value {
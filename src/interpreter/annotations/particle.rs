@@ -0,0 +1,310 @@
+use std::f32::consts::{PI, TAU};
+
+use crate::types::prelude::*;
+
+/// first pack format where particle options are given as NBT, like `dust{color:[1f,0f,0f],scale:1f}`
+const PARTICLE_NBT_VERSION: u8 = 41;
+
+/// interpret a particle annotation like `@particle { kind: dust, color: [1, 0, 0], shape: circle, radius: 2 }`
+#[allow(clippy::too_many_lines)]
+pub(super) fn particle(src: &Syntax) -> SResult<VecCmd> {
+    let Syntax::Object(obj) = src else {
+        let Ok(kind) = RStr::try_from(src) else {
+            return Err(format!("Particle annotation expects an object or particle name, not `{src:?}`"))
+        };
+        return Ok(vec![Command::Particle {
+            kind,
+            pos: Coordinate::here(),
+            delta: [0.0; 3],
+            speed: 0.0,
+            count: 1,
+            force: false,
+            viewers: None,
+        }]
+        .into());
+    };
+    let mut kind: Option<RStr> = None;
+    let mut pos = Coordinate::here();
+    let mut delta = [0.0; 3];
+    let mut speed = 0.0;
+    let mut count = 1;
+    let mut force = false;
+    let mut viewers = None;
+    let mut options = ParticleOptions::default();
+    let mut shape: Option<RStr> = None;
+    let mut radius = 1.0;
+    let mut points = 16;
+    let mut end = [0.0; 3];
+    let mut height = 2.0;
+    let mut turns = 2.0;
+    for (k, v) in obj {
+        match &**k {
+            "kind" | "particle" => {
+                kind = Some(RStr::try_from(v).map_err(|_| {
+                    format!("Expected a string or identifier for particle kind; got `{v:?}`")
+                })?);
+            }
+            "pos" | "position" => pos = Coordinate::try_from(v)?,
+            "delta" | "spread" => delta = floats(v, k)?,
+            "speed" => speed = float(v, k)?,
+            "count" => match v {
+                Syntax::Integer(int) if *int >= 0 => count = *int,
+                other => {
+                    return Err(format!(
+                        "Expected a non-negative integer for particle count; got `{other:?}`"
+                    ))
+                }
+            },
+            "force" => match v {
+                Syntax::Identifier(bool) if matches!(&**bool, "true" | "false") => {
+                    force = &**bool == "true";
+                }
+                other => return Err(format!("Expected true or false for `force`; got `{other:?}`")),
+            },
+            "viewers" => {
+                let Syntax::Selector(selector) = v else {
+                    return Err(format!("Expected a selector for particle viewers; got `{v:?}`"))
+                };
+                viewers = Some(selector.stringify()?);
+            }
+            "color" | "from_color" => options.color = Some(color(v, k)?),
+            "to_color" => options.to_color = Some(color(v, k)?),
+            "size" | "scale" => options.size = float(v, k)?,
+            "block" | "item" => {
+                let Ok(id) = RStr::try_from(v) else {
+                    return Err(format!("Expected a string or identifier for particle `{k}`; got `{v:?}`"))
+                };
+                options.id = Some(id);
+            }
+            "roll" => options.roll = float(v, k)?,
+            "delay" => match v {
+                Syntax::Integer(int) => options.delay = *int,
+                other => return Err(format!("Expected an integer for particle delay; got `{other:?}`")),
+            },
+            "shape" => match v {
+                Syntax::Identifier(name) if matches!(&**name, "line" | "circle" | "sphere" | "helix") => {
+                    shape = Some(name.clone());
+                }
+                other => {
+                    return Err(format!(
+                        "Unknown particle shape `{other:?}`; expected line, circle, sphere, or helix"
+                    ))
+                }
+            },
+            "radius" => radius = float(v, k)?,
+            "points" => match v {
+                Syntax::Integer(int) if *int >= 1 => points = *int,
+                other => {
+                    return Err(format!(
+                        "Expected a positive integer for shape points; got `{other:?}`"
+                    ))
+                }
+            },
+            "end" => end = floats(v, k)?,
+            "height" => height = float(v, k)?,
+            "turns" => turns = float(v, k)?,
+            other => return Err(format!("Invalid key for particle annotation: `{other}`")),
+        }
+    }
+    let Some(kind) = kind else {
+        return Err(String::from("Particle annotation must specify the particle; {... kind: flame}"))
+    };
+    let kind = options.stringify(&kind)?;
+    #[allow(clippy::cast_precision_loss)]
+    let offsets: Vec<[f32; 3]> = match shape.as_deref() {
+        None => vec![[0.0; 3]],
+        Some("line") => {
+            let segments = (points - 1).max(1) as f32;
+            (0..points)
+                .map(|i| end.map(|axis| axis * i as f32 / segments))
+                .collect()
+        }
+        Some("circle") => (0..points)
+            .map(|i| {
+                let angle = TAU * i as f32 / points as f32;
+                [radius * angle.cos(), 0.0, radius * angle.sin()]
+            })
+            .collect(),
+        // spread the points evenly with a fibonacci lattice
+        Some("sphere") => (0..points)
+            .map(|i| {
+                let y = 1.0 - 2.0 * (i as f32 + 0.5) / points as f32;
+                let ring = (1.0 - y * y).sqrt();
+                let angle = PI * (3.0 - 5f32.sqrt()) * i as f32;
+                [
+                    radius * ring * angle.cos(),
+                    radius * y,
+                    radius * ring * angle.sin(),
+                ]
+            })
+            .collect(),
+        Some(_) => {
+            let segments = (points - 1).max(1) as f32;
+            (0..points)
+                .map(|i| {
+                    let t = i as f32 / segments;
+                    let angle = TAU * turns * t;
+                    [radius * angle.cos(), height * t, radius * angle.sin()]
+                })
+                .collect()
+        }
+    };
+    Ok(kind.map(|kind| {
+        offsets
+            .iter()
+            .map(|offset| Command::Particle {
+                kind: kind.clone(),
+                pos: offset_coordinate(pos, *offset),
+                delta,
+                speed,
+                count,
+                force,
+                viewers: viewers.clone(),
+            })
+            .collect()
+    }))
+}
+
+/// options some particles need, like the color of `dust`
+struct ParticleOptions {
+    color: Option<[f32; 3]>,
+    to_color: Option<[f32; 3]>,
+    size: f32,
+    id: Option<RStr>,
+    roll: f32,
+    delay: i32,
+}
+
+impl Default for ParticleOptions {
+    fn default() -> Self {
+        Self {
+            color: None,
+            to_color: None,
+            size: 1.0,
+            id: None,
+            roll: 0.0,
+            delay: 0,
+        }
+    }
+}
+
+impl ParticleOptions {
+    /// get the particle argument, with options written the way each version expects them
+    fn stringify(&self, kind: &str) -> SResult<Versioned<RStr>> {
+        let name = kind.trim_start_matches("minecraft:");
+        let color_nbt = |[r, g, b]: [f32; 3]| nbt!([r, g, b]);
+        let (old, new): (String, Nbt) = match name {
+            "dust" => {
+                let Some(color) = self.color else {
+                    return Err(String::from("`dust` particles need a color; {... color: [1, 0, 0]}"))
+                };
+                let [r, g, b] = color;
+                (
+                    format!("{kind} {r} {g} {b} {}", self.size),
+                    nbt!({color: color_nbt(color), scale: self.size}),
+                )
+            }
+            "dust_color_transition" => {
+                let (Some(from), Some(to)) = (self.color, self.to_color) else {
+                    return Err(String::from("`dust_color_transition` particles need a color and to_color"))
+                };
+                let [r, g, b] = from;
+                let [r2, g2, b2] = to;
+                (
+                    format!("{kind} {r} {g} {b} {} {r2} {g2} {b2}", self.size),
+                    nbt!({from_color: color_nbt(from), scale: self.size, to_color: color_nbt(to)}),
+                )
+            }
+            "block" | "block_marker" | "falling_dust" => {
+                let Some(block) = &self.id else {
+                    return Err(format!("`{name}` particles need a block; {{... block: stone}}"))
+                };
+                (
+                    format!("{kind} {block}"),
+                    nbt!({block_state: block_state(block)?}),
+                )
+            }
+            "item" => {
+                let Some(item) = &self.id else {
+                    return Err(String::from("`item` particles need an item; {... item: diamond}"))
+                };
+                (format!("{kind} {item}"), nbt!({item: nbt!({id: item})}))
+            }
+            "sculk_charge" => (
+                format!("{kind} {}", self.roll),
+                nbt!({roll: self.roll}),
+            ),
+            "shriek" => (
+                format!("{kind} {}", self.delay),
+                nbt!({delay: self.delay}),
+            ),
+            _ => return Ok(RStr::from(kind).into()),
+        };
+        let mut versioned: Versioned<RStr> = RStr::from(old).into();
+        versioned.add_version(PARTICLE_NBT_VERSION, format!("{kind}{new}").into());
+        Ok(versioned)
+    }
+}
+
+/// get a block state compound from a block like `oak_log[axis=y]`
+fn block_state(block: &str) -> SResult<Nbt> {
+    let Some((name, properties)) = block.split_once('[') else {
+        return Ok(nbt!({Name: block}));
+    };
+    let Some(properties) = properties.strip_suffix(']') else {
+        return Err(format!("Invalid block state `{block}`"))
+    };
+    let properties = properties
+        .split(',')
+        .filter(|property| !property.trim().is_empty())
+        .map(|property| {
+            property
+                .split_once('=')
+                .map(|(k, v)| (RStr::from(k.trim()), Nbt::from(v.trim())))
+                .ok_or_else(|| format!("Invalid block state `{block}`"))
+        })
+        .collect::<SResult<std::collections::BTreeMap<_, _>>>()?;
+    Ok(nbt!({Name: name, Properties: properties}))
+}
+
+/// move a coordinate by an offset, rounded so the commands stay readable
+fn offset_coordinate(pos: Coordinate, offset: [f32; 3]) -> Coordinate {
+    // adding 0 turns -0 into 0
+    let [x, y, z] = offset.map(|axis| (axis * 1000.0).round() / 1000.0 + 0.0);
+    match pos {
+        Coordinate::Linear(x_rel, x0, y_rel, y0, z_rel, z0) => {
+            Coordinate::Linear(x_rel, x0 + x, y_rel, y0 + y, z_rel, z0 + z)
+        }
+        Coordinate::Angular(left, up, forward) => {
+            Coordinate::Angular(left + x, up + y, forward + z)
+        }
+    }
+}
+
+fn float(src: &Syntax, key: &str) -> SResult<f32> {
+    match src {
+        #[allow(clippy::cast_precision_loss)]
+        Syntax::Integer(int) => Ok(*int as f32),
+        Syntax::Float(float) => Ok(*float),
+        other => Err(format!("Expected a number for particle `{key}`; got `{other:?}`")),
+    }
+}
+
+fn floats(src: &Syntax, key: &str) -> SResult<[f32; 3]> {
+    let Syntax::Array(arr) = src else {
+        return Err(format!("Expected 3 numbers for particle `{key}`; got `{src:?}`"))
+    };
+    let [x, y, z] = &arr[..] else {
+        return Err(format!("Expected 3 numbers for particle `{key}`; got `{src:?}`"))
+    };
+    Ok([float(x, key)?, float(y, key)?, float(z, key)?])
+}
+
+/// an rgb color with each channel from 0 to 1
+fn color(src: &Syntax, key: &str) -> SResult<[f32; 3]> {
+    let color = floats(src, key)?;
+    if color.iter().any(|channel| !(0.0..=1.0).contains(channel)) {
+        return Err(format!("Particle `{key}` channels must be between 0 and 1; got `{src:?}`"));
+    }
+    Ok(color)
+}
@@ -0,0 +1,362 @@
+use crate::types::prelude::*;
+
+/// the most blocks one `fill` or `clone` command can change
+const VOLUME_LIMIT: i64 = 32768;
+/// the side length of the cubes a large fill is split into; `CHUNK_SIZE³` is exactly the limit
+const CHUNK_SIZE: i32 = 32;
+
+/// one axis of a region: whether it's relative, and its lowest and highest block
+type Axis = (bool, i32, i32);
+
+/// place a block like `@setblock { pos: (~ ~1 ~), block: chest, states: { facing: north } }`
+pub(super) fn setblock(src: &Syntax) -> SResult<VecCmd> {
+    let Syntax::Object(obj) = src else {
+        return Err(format!("Setblock annotation expects an object, not `{src:?}`"))
+    };
+    let mut pos = Coordinate::here();
+    let mut mode = None;
+    for (k, v) in obj {
+        match &**k {
+            "pos" | "position" => pos = Coordinate::try_from(v)?,
+            "mode" => match v {
+                Syntax::Identifier(m) if matches!(&**m, "replace" | "destroy" | "keep") => {
+                    mode = Some(m.clone());
+                }
+                other => {
+                    return Err(format!(
+                        "Invalid setblock mode `{other:?}`; expected replace, destroy, or keep"
+                    ))
+                }
+            },
+            "block" | "states" | "nbt" => {}
+            other => return Err(format!("Invalid key for setblock annotation: `{other}`")),
+        }
+    }
+    Ok(vec![Command::SetBlock {
+        pos,
+        block: block(obj, "setblock")?,
+        mode,
+    }]
+    .into())
+}
+
+/// fill a region like `@fill { from: (~-5 ~ ~-5), to: (~5 ~5 ~5), block: glass, mode: hollow }`
+///
+/// regions bigger than the volume limit are split into several fills
+pub(super) fn fill(src: &Syntax) -> SResult<VecCmd> {
+    let Syntax::Object(obj) = src else {
+        return Err(format!("Fill annotation expects an object, not `{src:?}`"))
+    };
+    let mut start = None;
+    let mut end = None;
+    let mut mode: Option<RStr> = None;
+    let mut filter = None;
+    for (k, v) in obj {
+        match &**k {
+            "from" | "start" => start = Some(Coordinate::try_from(v)?),
+            "to" | "end" => end = Some(Coordinate::try_from(v)?),
+            "mode" => match v {
+                Syntax::Identifier(m)
+                    if matches!(&**m, "replace" | "hollow" | "outline" | "destroy" | "keep") =>
+                {
+                    mode = Some(m.clone());
+                }
+                other => return Err(format!("Invalid fill mode `{other:?}`; expected replace, hollow, outline, destroy, or keep")),
+            },
+            "filter" => {
+                filter = Some(RStr::try_from(v).map_err(|_| {
+                    format!("Expected a block or block tag to replace; got `{v:?}`")
+                })?);
+            }
+            "block" | "states" | "nbt" => {}
+            other => return Err(format!("Invalid key for fill annotation: `{other}`")),
+        }
+    }
+    let (Some(start), Some(end)) = (start, end) else {
+        return Err(String::from("Fill annotation needs a region; {... from: (...), to: (...)}"))
+    };
+    let block = block(obj, "fill")?;
+    let mode: Option<RStr> = match (mode.as_deref(), filter) {
+        (None | Some("replace"), Some(filter)) => Some(format!("replace {filter}").into()),
+        (Some(m), Some(_)) => return Err(format!("Fill `filter` only works with the replace mode; got `{m}`")),
+        // replace is the default
+        (_, None) => mode.clone().filter(|m| &**m != "replace"),
+    };
+    let fill = |start, end, mode: Option<RStr>| Command::Fill {
+        start,
+        end,
+        block: block.clone(),
+        mode,
+    };
+    let Some(axes) = region(start, end) else {
+        // the size isn't known until the command runs
+        return Ok(vec![fill(start, end, mode)].into());
+    };
+    if volume(axes) <= VOLUME_LIMIT {
+        return Ok(vec![fill(start, end, mode)].into());
+    }
+    let cmds = match mode.as_deref() {
+        // the outer layer, which is the same as outline without touching the inside
+        Some("outline" | "hollow") => {
+            let mut cmds: Vec<Command> = faces(axes)
+                .into_iter()
+                .flat_map(chunks)
+                .map(|(start, end)| fill(start, end, None))
+                .collect();
+            if mode.as_deref() == Some("hollow") {
+                if let Some(inside) = shrink(axes) {
+                    cmds.extend(chunks(inside).into_iter().map(|(start, end)| Command::Fill {
+                        start,
+                        end,
+                        block: "air".into(),
+                        mode: None,
+                    }));
+                }
+            }
+            cmds
+        }
+        _ => chunks(axes)
+            .into_iter()
+            .map(|(start, end)| fill(start, end, mode.clone()))
+            .collect(),
+    };
+    println!(
+        "\x1b[33mWARN\x1b[0m\tFill from `{start}` to `{end}` is {} blocks, more than the limit of {VOLUME_LIMIT}; splitting it into {} commands",
+        volume(axes),
+        cmds.len()
+    );
+    Ok(cmds.into())
+}
+
+/// copy a region like `@clone { from: (0 64 0), to: (9 70 9), destination: (~ ~ ~), mask: masked, mode: move }`
+pub(super) fn clone(src: &Syntax) -> SResult<VecCmd> {
+    let Syntax::Object(obj) = src else {
+        return Err(format!("Clone annotation expects an object, not `{src:?}`"))
+    };
+    let mut start = None;
+    let mut end = None;
+    let mut destination = None;
+    let mut mask: Option<RStr> = None;
+    let mut filter = None;
+    let mut mode: RStr = "normal".into();
+    for (k, v) in obj {
+        match (&**k, v) {
+            ("from" | "start", _) => start = Some(Coordinate::try_from(v)?),
+            ("to" | "end", _) => end = Some(Coordinate::try_from(v)?),
+            ("destination" | "dest", _) => destination = Some(Coordinate::try_from(v)?),
+            ("mask", Syntax::Identifier(m)) if matches!(&**m, "replace" | "masked" | "filtered") => {
+                mask = Some(m.clone());
+            }
+            ("mode", Syntax::Identifier(m)) if matches!(&**m, "normal" | "force" | "move") => {
+                mode = m.clone();
+            }
+            ("filter", _) => {
+                filter = Some(RStr::try_from(v).map_err(|_| {
+                    format!("Expected a block or block tag to clone; got `{v:?}`")
+                })?);
+            }
+            ("mask", other) => return Err(format!("Invalid clone mask `{other:?}`; expected replace, masked, or filtered")),
+            ("mode", other) => return Err(format!("Invalid clone mode `{other:?}`; expected normal, force, or move")),
+            (other, _) => return Err(format!("Invalid key for clone annotation: `{other}`")),
+        }
+    }
+    let (Some(start), Some(end), Some(destination)) = (start, end, destination) else {
+        return Err(String::from("Clone annotation needs a region and destination; {... from: (...), to: (...), destination: (...)}"))
+    };
+    let mask: RStr = match (mask.as_deref(), filter) {
+        (None | Some("filtered"), Some(filter)) => format!("filtered {filter}").into(),
+        (Some("filtered"), None) => return Err(String::from("Clone mask `filtered` needs a `filter` block")),
+        (Some(m), Some(_)) => return Err(format!("Clone `filter` only works with the filtered mask; got `{m}`")),
+        (Some(m), None) => m.into(),
+        (None, None) => "replace".into(),
+    };
+    let clone = |start, end, destination| Command::Clone {
+        start,
+        end,
+        destination,
+        mask: mask.clone(),
+        mode: mode.clone(),
+    };
+    let (Some(axes), Coordinate::Linear(rel_x, to_x, rel_y, to_y, rel_z, to_z)) = (region(start, end), destination) else {
+        return Ok(vec![clone(start, end, destination)].into());
+    };
+    if volume(axes) <= VOLUME_LIMIT {
+        return Ok(vec![clone(start, end, destination)].into());
+    }
+    let mut chunks = chunks(axes);
+    if [rel_x, rel_y, rel_z] == axes.map(|(rel, _, _)| rel) {
+        // copy the chunks furthest along the direction of travel first, so chunks that overlap
+        // the source have read their blocks before they're overwritten
+        order_chunks(&mut chunks, axes, [to_x, to_y, to_z]);
+    } else {
+        println!("\x1b[33mWARN\x1b[0m\tClone from `{start}` to `{end}` is split, but its destination `{destination}` is relative differently, so the pieces might overwrite blocks they still need to copy");
+    }
+    // the destination is where the lowest corner of the region ends up
+    let cmds: Vec<Command> = chunks
+        .into_iter()
+        .map(|(chunk_start, chunk_end)| {
+            let Coordinate::Linear(_, x, _, y, _, z) = chunk_start else {
+                unreachable!()
+            };
+            #[allow(clippy::cast_precision_loss)]
+            let offset = [
+                x - axes[0].1 as f32,
+                y - axes[1].1 as f32,
+                z - axes[2].1 as f32,
+            ];
+            clone(chunk_start, chunk_end, shift(destination, offset))
+        })
+        .collect();
+    println!(
+        "\x1b[33mWARN\x1b[0m\tClone from `{start}` to `{end}` is {} blocks, more than the limit of {VOLUME_LIMIT}; splitting it into {} commands",
+        volume(axes),
+        cmds.len()
+    );
+    Ok(cmds.into())
+}
+
+/// sort chunks so the ones furthest along each axis the destination moves toward come first
+fn order_chunks(chunks: &mut [(Coordinate, Coordinate)], axes: [Axis; 3], destination: [f32; 3]) {
+    let mut direction = [1.0; 3];
+    for (i, (_, low, _)) in axes.into_iter().enumerate() {
+        #[allow(clippy::cast_precision_loss)]
+        if destination[i] > low as f32 {
+            direction[i] = -1.0;
+        }
+    }
+    chunks.sort_by(|(a, _), (b, _)| {
+        let key = |pos: &Coordinate| {
+            let Coordinate::Linear(_, x, _, y, _, z) = pos else {
+                unreachable!()
+            };
+            [x * direction[0], y * direction[1], z * direction[2]]
+        };
+        key(a).partial_cmp(&key(b)).unwrap_or(std::cmp::Ordering::Equal)
+    });
+}
+
+/// get a block with its states and NBT, like `chest[facing=north]{Lock:"key"}`
+fn block(obj: &std::collections::BTreeMap<RStr, Syntax>, annotation: &str) -> SResult<RStr> {
+    let Some(block) = obj.get("block") else {
+        return Err(format!("The {annotation} annotation must specify a block; {{... block: stone}}"))
+    };
+    let mut block = String::try_from(block)
+        .map_err(|_| format!("Expected a string or identifier for the block; got `{block:?}`"))?;
+    match obj.get("states") {
+        Some(Syntax::Object(states)) => {
+            if block.contains('[') {
+                return Err(format!("Block `{block}` already has states"));
+            }
+            let states = states
+                .iter()
+                .map(|(k, v)| match v {
+                    Syntax::Identifier(v) | Syntax::String(v) => Ok(format!("{k}={v}")),
+                    Syntax::Integer(v) => Ok(format!("{k}={v}")),
+                    other => Err(format!("Invalid block state `{k}`: `{other:?}`")),
+                })
+                .collect::<SResult<Vec<_>>>()?;
+            block = format!("{block}[{}]", states.join(","));
+        }
+        Some(other) => return Err(format!("Block states must be an object; got `{other:?}`")),
+        None => {}
+    }
+    match obj.get("nbt") {
+        Some(nbt @ Syntax::Object(_)) => block.push_str(&Nbt::try_from(nbt)?.to_string()),
+        Some(other) => return Err(format!("Block NBT must be an object; got `{other:?}`")),
+        None => {}
+    }
+    Ok(block.into())
+}
+
+/// get the axes of a region, if its size is known at compile time
+fn region(start: Coordinate, end: Coordinate) -> Option<[Axis; 3]> {
+    let (
+        Coordinate::Linear(x1_rel, x1, y1_rel, y1, z1_rel, z1),
+        Coordinate::Linear(x2_rel, x2, y2_rel, y2, z2_rel, z2),
+    ) = (start, end) else {
+        return None
+    };
+    #[allow(clippy::cast_possible_truncation)]
+    let axis = |rel1: bool, a: f32, rel2: bool, b: f32| -> Option<Axis> {
+        let (a, b) = (a.floor() as i32, b.floor() as i32);
+        (rel1 == rel2).then_some((rel1, a.min(b), a.max(b)))
+    };
+    Some([
+        axis(x1_rel, x1, x2_rel, x2)?,
+        axis(y1_rel, y1, y2_rel, y2)?,
+        axis(z1_rel, z1, z2_rel, z2)?,
+    ])
+}
+
+fn volume(axes: [Axis; 3]) -> i64 {
+    axes.iter()
+        .map(|(_, low, high)| i64::from(high - low) + 1)
+        .product()
+}
+
+/// turn axes back into the corners of a region
+#[allow(clippy::cast_precision_loss)]
+const fn corners([(x_rel, x1, x2), (y_rel, y1, y2), (z_rel, z1, z2)]: [Axis; 3]) -> (Coordinate, Coordinate) {
+    (
+        Coordinate::Linear(x_rel, x1 as f32, y_rel, y1 as f32, z_rel, z1 as f32),
+        Coordinate::Linear(x_rel, x2 as f32, y_rel, y2 as f32, z_rel, z2 as f32),
+    )
+}
+
+/// split a region into cubes small enough for one command
+fn chunks(axes: [Axis; 3]) -> Vec<(Coordinate, Coordinate)> {
+    let split = |(rel, low, high): Axis| -> Vec<Axis> {
+        (low..=high)
+            .step_by(CHUNK_SIZE as usize)
+            .map(|from| (rel, from, (from + CHUNK_SIZE - 1).min(high)))
+            .collect()
+    };
+    let mut out = Vec::new();
+    for x in split(axes[0]) {
+        for y in split(axes[1]) {
+            for z in split(axes[2]) {
+                out.push(corners([x, y, z]));
+            }
+        }
+    }
+    out
+}
+
+/// the six faces of a region, without overlapping each other
+fn faces(axes: [Axis; 3]) -> Vec<[Axis; 3]> {
+    let [x, y, z] = axes;
+    let mut faces = vec![[x, (y.0, y.1, y.1), z]];
+    if y.2 > y.1 {
+        faces.push([x, (y.0, y.2, y.2), z]);
+    }
+    let Some(middle_y) = (y.2 - y.1 > 1).then_some((y.0, y.1 + 1, y.2 - 1)) else {
+        return faces
+    };
+    faces.push([(x.0, x.1, x.1), middle_y, z]);
+    if x.2 > x.1 {
+        faces.push([(x.0, x.2, x.2), middle_y, z]);
+    }
+    let Some(middle_x) = (x.2 - x.1 > 1).then_some((x.0, x.1 + 1, x.2 - 1)) else {
+        return faces
+    };
+    faces.push([middle_x, middle_y, (z.0, z.1, z.1)]);
+    if z.2 > z.1 {
+        faces.push([middle_x, middle_y, (z.0, z.2, z.2)]);
+    }
+    faces
+}
+
+/// the inside of a region, without its outer layer
+fn shrink(axes: [Axis; 3]) -> Option<[Axis; 3]> {
+    let shrink = |(rel, low, high): Axis| (high - low > 1).then_some((rel, low + 1, high - 1));
+    Some([shrink(axes[0])?, shrink(axes[1])?, shrink(axes[2])?])
+}
+
+fn shift(pos: Coordinate, [dx, dy, dz]: [f32; 3]) -> Coordinate {
+    match pos {
+        Coordinate::Linear(x_rel, x, y_rel, y, z_rel, z) => {
+            Coordinate::Linear(x_rel, x + dx, y_rel, y + dy, z_rel, z + dz)
+        }
+        Coordinate::Angular(..) => pos,
+    }
+}
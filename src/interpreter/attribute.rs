@@ -0,0 +1,194 @@
+use super::property::is_single;
+use crate::{types::prelude::*, Config};
+
+/// first pack format where attribute modifiers are identified by a resource location instead of a UUID and name
+pub(super) const MODIFIER_ID_VERSION: u8 = 48;
+/// first pack format where attributes lost their `generic.` style prefixes
+pub(super) const UNPREFIXED_VERSION: u8 = 57;
+
+/// get the command that reads an attribute, like `x = @s::attr.generic.max_health`
+pub(super) fn get(
+    selector: &Selector<Syntax>,
+    attribute: &str,
+    scale: i32,
+) -> SResult<Versioned<Command>> {
+    let target = selector.stringify()?;
+    if !is_single(&target) {
+        return Err(format!("Can't read `{target}::attr.{attribute}` from more than one entity; add `limit=1`"));
+    }
+    Ok(versioned(attribute, |attribute, _| Command::AttributeGet {
+        target: target.clone(),
+        attribute,
+        scale,
+    }))
+}
+
+/// interpret an operation on an attribute, like `@s::attr.generic.max_health = 40`
+///
+/// `+= { name: "...", amount: 4, operation: add }` adds a modifier, and `-= "..."` removes it
+pub(super) fn operation(
+    selector: &Selector<Syntax>,
+    attribute: &str,
+    op: Operation,
+    rhs: &Syntax,
+    config: &Config,
+) -> SResult<VecCmd> {
+    // `attribute` only takes one entity, so change each one as itself
+    let (options, target) = match selector.stringify()? {
+        target if is_single(&target) => (Vec::new(), target),
+        target => (vec![ExecuteOption::As(target)], Selector::s()),
+    };
+    let cmd = match (op, rhs) {
+        (Operation::Equal, Syntax::Integer(_) | Syntax::Float(_)) => {
+            #[allow(clippy::cast_precision_loss)]
+            let value = match rhs {
+                Syntax::Integer(int) => *int as f32,
+                Syntax::Float(float) => *float,
+                _ => unreachable!(),
+            };
+            versioned(attribute, |attribute, _| Command::AttributeBaseSet {
+                target: target.clone(),
+                attribute,
+                value,
+            })
+        }
+        (Operation::AddEq, Syntax::Object(obj)) => {
+            let mut name = None;
+            let mut amount = None;
+            let mut operation = ("add", "add_value");
+            for (k, v) in obj {
+                match (&**k, v) {
+                    ("name" | "id", Syntax::String(n) | Syntax::Identifier(n)) => name = Some(n.clone()),
+                    #[allow(clippy::cast_precision_loss)]
+                    ("amount" | "value", Syntax::Integer(int)) => amount = Some(*int as f32),
+                    ("amount" | "value", Syntax::Float(float)) => amount = Some(*float),
+                    ("operation", Syntax::Identifier(o)) => {
+                        operation = match &**o {
+                            "add" | "add_value" => ("add", "add_value"),
+                            "multiply_base" | "add_multiplied_base" => ("multiply_base", "add_multiplied_base"),
+                            "multiply" | "add_multiplied_total" => ("multiply", "add_multiplied_total"),
+                            _ => return Err(format!("Unknown attribute modifier operation `{o}`; expected add, multiply_base, or multiply")),
+                        }
+                    }
+                    _ => return Err(format!("Invalid attribute modifier property `{k}: {v:?}`")),
+                }
+            }
+            let (Some(name), Some(amount)) = (name, amount) else {
+                return Err(format!("Attribute modifiers need a name and amount; {{ name: \"bonus\", amount: 4 }}; got `{rhs:?}`"))
+            };
+            versioned(attribute, |attribute, version| {
+                if version >= MODIFIER_ID_VERSION {
+                    Command::AttributeModifierAdd {
+                        target: target.clone(),
+                        attribute,
+                        id: namespaced(&name, &config.namespace).into(),
+                        name: None,
+                        amount,
+                        operation: operation.1.into(),
+                    }
+                } else {
+                    Command::AttributeModifierAdd {
+                        target: target.clone(),
+                        attribute,
+                        id: uuid(&name, config).into(),
+                        name: Some(Nbt::from(&name).to_string().into()),
+                        amount,
+                        operation: operation.0.into(),
+                    }
+                }
+            })
+        }
+        (Operation::SubEq, Syntax::String(name) | Syntax::Identifier(name)) => {
+            versioned(attribute, |attribute, version| Command::AttributeModifierRemove {
+                target: target.clone(),
+                attribute,
+                id: if version >= MODIFIER_ID_VERSION {
+                    namespaced(name, &config.namespace).into()
+                } else {
+                    uuid(name, config).into()
+                },
+            })
+        }
+        (Operation::AddEq | Operation::SubEq, Syntax::Integer(_) | Syntax::Float(_)) => {
+            return Err(format!("Can't `{op}` a number to an attribute; add a named modifier with `@s::attr.{attribute} += {{ name: \"...\", amount: {rhs:?} }}` instead"))
+        }
+        _ => return Err(format!("Can't operate `@s::attr.{attribute} {op} {rhs:?}`; attributes can be set to a number, `+=` a modifier, or `-=` a modifier's name")),
+    };
+    if options.is_empty() {
+        return Ok(cmd.into_vec());
+    }
+    Ok(cmd
+        .map(|cmd| Command::Execute {
+            options: options.clone(),
+            cmd: Box::new(cmd),
+        })
+        .into_vec())
+}
+
+/// make an attribute command for each version it's written differently in
+fn versioned<F: Fn(RStr, u8) -> Command>(attribute: &str, make: F) -> Versioned<Command> {
+    let unprefixed = unprefixed(attribute);
+    let base = make(attribute.into(), 0);
+    let with_ids = make(attribute.into(), MODIFIER_ID_VERSION);
+    let latest = make(unprefixed.into(), UNPREFIXED_VERSION);
+    let mut cmd: Versioned<Command> = base.clone().into();
+    if with_ids != base {
+        cmd.add_version(MODIFIER_ID_VERSION, with_ids.clone());
+    }
+    if latest != with_ids {
+        cmd.add_version(UNPREFIXED_VERSION, latest);
+    }
+    cmd
+}
+
+/// an attribute's name without its `generic.` style prefix
+pub(super) fn unprefixed(attribute: &str) -> &str {
+    attribute
+        .split_once('.')
+        .map_or(attribute, |(_, name)| name)
+}
+
+/// a UUID for a modifier that's the same every time the pack is compiled, so it can be removed later
+fn uuid(name: &str, config: &Config) -> String {
+    let (high, low) = uuid_bits(name, config);
+    format!(
+        "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+        high >> 32,
+        (high >> 16) & 0xffff,
+        high & 0xffff,
+        low >> 48,
+        low & 0xffff_ffff_ffff
+    )
+}
+
+/// the same UUID as [`uuid`], written as the int array NBT stores it as
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+pub(super) fn uuid_ints(name: &str, config: &Config) -> [i32; 4] {
+    let (high, low) = uuid_bits(name, config);
+    [(high >> 32) as i32, high as i32, (low >> 32) as i32, low as i32]
+}
+
+fn uuid_bits(name: &str, config: &Config) -> (u64, u64) {
+    let high = fnv1a(&[&config.namespace, name]);
+    let low = fnv1a(&[&config.namespace, name, "attribute_modifier"]);
+    // mark it as a version 4 UUID
+    let high = (high & !0xf000) | 0x4000;
+    let low = (low & !(0xc << 60)) | (0x8 << 60);
+    (high, low)
+}
+
+/// 64-bit FNV-1a over each part, separated by a zero byte
+///
+/// unlike `get_hash`, this never changes between compiler versions, so modifiers that are already
+/// applied can still be removed after the pack is rebuilt
+fn fnv1a(parts: &[&str]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for (i, part) in parts.iter().enumerate() {
+        let separator: &[u8] = if i == 0 { &[] } else { &[0] };
+        for byte in separator.iter().chain(part.as_bytes()) {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
+}
@@ -5,9 +5,10 @@ use std::{
 
 use lazy_regex::lazy_regex;
 
-use super::{get_data_location, inner_interpret, InterRepr};
+use super::{get_data_location, inner_interpret, resolve_enum, InterRepr};
 use crate::{interpreter::operation::operation, types::prelude::*, Config};
 
+#[allow(clippy::too_many_lines)]
 pub(super) fn block(
     block_type: BlockType,
     lhs: &Syntax,
@@ -18,6 +19,36 @@ pub(super) fn block(
     config: &Config,
 ) -> SResult<VecCmd> {
     match (block_type, lhs, body) {
+        // if @s has marked {}
+        (
+            BlockType::If | BlockType::Unless,
+            Syntax::BinaryOp {
+                lhs: left,
+                operation: Operation::Has,
+                rhs: right,
+            },
+            _,
+        ) => {
+            let Syntax::Selector(selector) = &**left else {
+                return Err(format!("Only selectors can have tags; got `{left:?}`"))
+            };
+            if selector.args.contains_key("tag") {
+                return Err(format!("`{selector:?}` already checks a tag; nest another `if` to test `{right:?}`"));
+            }
+            let tag = String::try_from(&**right)?;
+            state.tested_tags.insert(tag.trim_start_matches('!').into());
+            let inner = inner_interpret(body, state, path, src_files, config)?;
+            Ok(Command::execute(
+                &[ExecuteOption::IfEntity {
+                    invert: block_type == BlockType::Unless,
+                    selector: selector.stringify()?.with_property("tag", tag),
+                }],
+                inner,
+                &format!("__internal__/if_{:x}", get_hash(body)),
+                state,
+            )
+            .into_vec())
+        }
         // if x=1 {}
         (
             BlockType::If | BlockType::Unless,
@@ -41,10 +72,56 @@ pub(super) fn block(
             )?);
             Ok(commands)
         }
+        // if block (~ ~-1 ~) grass_block {}
+        (BlockType::If | BlockType::Unless, Syntax::Condition(kind, args), _) => {
+            let option = condition(block_type == BlockType::Unless, kind, args)?;
+            let inner = inner_interpret(body, state, path, src_files, config)?;
+            Ok(Command::execute(
+                &[option],
+                inner,
+                &format!("__internal__/if_{:x}", get_hash(body)),
+                state,
+            )
+            .into_vec())
+        }
+        // for entity in @e[...] {}
+        (
+            BlockType::For,
+            Syntax::BinaryOp {
+                lhs: left,
+                operation: Operation::In,
+                rhs: right,
+            },
+            _,
+        ) if right.is_selector() => {
+            let (mut commands, left) = get_data_location(left)?;
+            let Syntax::Selector(selector) = &**right else {
+                unreachable!()
+            };
+            commands.extend(super::selector_block::for_each(
+                left, selector, body, state, path, src_files, config,
+            )?);
+            Ok(commands)
+        }
         // for _ in 1..10 {}
         (
-            BlockType::For
-            | BlockType::While
+            BlockType::For,
+            Syntax::BinaryOp {
+                lhs: left,
+                operation: Operation::In,
+                rhs: right,
+            },
+            _,
+        ) => {
+            let (mut commands, left) = get_data_location(left)?;
+            commands.extend(for_block(
+                left, right, body, state, path, src_files, config,
+            )?);
+            Ok(commands)
+        }
+        // while x < 10 {}
+        (
+            BlockType::While
             | BlockType::Until
             | BlockType::DoWhile
             | BlockType::DoUntil,
@@ -57,7 +134,7 @@ pub(super) fn block(
         ) => {
             let (mut commands, left) = get_data_location(left)?;
             commands.extend(loop_block(
-                block_type, left, *op, right, body, state, path, src_files, config,
+                block_type, &left, *op, right, body, state, path, src_files, config,
             )?);
             Ok(commands)
         }
@@ -96,9 +173,25 @@ pub(super) fn block(
         (BlockType::Async, Syntax::Identifier(ident) | Syntax::String(ident), _) => {
             async_block(body, ident, state, path, src_files, config)
         }
+        // summon zombie (~ ~1 ~) { nbt: {...}, tags: [...] } { ... }
+        (BlockType::Summon, Syntax::Array(arr), _) => {
+            let [entity, pos, properties] = &arr[..] else {
+                unreachable!()
+            };
+            summon_block(entity, pos, properties, body, state, path, src_files, config)
+        }
+        // summon zombie { nbt: {...} }
+        (BlockType::Summon, Syntax::Identifier(_) | Syntax::String(_), Syntax::Object(_)) => {
+            summon_block(lhs, &Syntax::Unit, body, &Syntax::Unit, state, path, src_files, config)
+        }
         // on owner { ... }
         (
-            BlockType::On | BlockType::Summon | BlockType::Anchored,
+            BlockType::On
+            | BlockType::Summon
+            | BlockType::Anchored
+            | BlockType::In
+            | BlockType::Align
+            | BlockType::PositionedOver,
             Syntax::Identifier(ident) | Syntax::String(ident),
             _,
         ) => ident_block(
@@ -128,6 +221,66 @@ pub(super) fn block(
     }
 }
 
+/// get the execute option for a vanilla condition, like `block (~ ~-1 ~) grass_block`
+fn condition(invert: bool, kind: &str, args: &[Syntax]) -> SResult<ExecuteOption> {
+    Ok(match (kind, args) {
+        ("block", [pos, block]) => ExecuteOption::IfBlock {
+            invert,
+            pos: Coordinate::try_from(pos)?,
+            value: String::try_from(block)?.into(),
+        },
+        ("blocks", [start, end, destination, mode @ ..]) => ExecuteOption::IfBlocks {
+            invert,
+            start: Coordinate::try_from(start)?,
+            end: Coordinate::try_from(end)?,
+            destination: Coordinate::try_from(destination)?,
+            masked: match mode {
+                [] => false,
+                [Syntax::Identifier(mode)] => &**mode == "masked",
+                _ => return Err(format!("Expected `all` or `masked` for `if blocks`; got `{mode:?}`")),
+            },
+        },
+        ("data", [Syntax::SelectorNbt(selector, path)]) => ExecuteOption::IfData {
+            invert,
+            location: NbtLocation::Entity(selector.stringify()?, path.clone()),
+        },
+        ("data", [Syntax::NbtStorage(path)]) => ExecuteOption::IfData {
+            invert,
+            location: NbtLocation::Storage(path.clone()),
+        },
+        ("data", [pos, path]) => ExecuteOption::IfBlockData {
+            invert,
+            pos: Coordinate::try_from(pos)?,
+            path: String::try_from(path)?.into(),
+        },
+        ("predicate", [predicate]) => ExecuteOption::IfPredicate {
+            invert,
+            predicate: String::try_from(predicate)?.into(),
+        },
+        ("biome", [pos, biome]) => ExecuteOption::IfBiome {
+            invert,
+            pos: Coordinate::try_from(pos)?,
+            biome: String::try_from(biome)?.into(),
+        },
+        ("dimension", [dimension]) => ExecuteOption::IfDimension {
+            invert,
+            dimension: String::try_from(dimension)?.into(),
+        },
+        ("loaded", [pos]) => ExecuteOption::IfLoaded {
+            invert,
+            pos: Coordinate::try_from(pos)?,
+        },
+        ("function", [function]) => {
+            println!("\x1b[33mWARN\x1b[0m\t`if function` needs Minecraft 1.20.3 or newer; older versions will fail to load the function");
+            ExecuteOption::IfFunction {
+                invert,
+                function: String::try_from(function)?.into(),
+            }
+        }
+        _ => return Err(format!("Invalid arguments for `if {kind}`: `{args:?}`")),
+    })
+}
+
 /// Handle a switch statement
 fn switch_block(
     lhs: &Syntax,
@@ -145,30 +298,181 @@ fn switch_block(
         state,
         config,
     )?;
-    for syn in arr.iter() {
-        let Syntax::Block(BlockType::Case, match_value, body) = syn else {
-                    return Err(format!("Expected `case` statement; got `{syn:?}`"))
+    // enum variants handled by the cases
+    let mut handled: BTreeMap<RStr, BTreeSet<RStr>> = BTreeMap::new();
+    // the body and function name of each arm
+    let mut arms: Vec<(VecCmd, RStr)> = Vec::new();
+    let mut ranges: Vec<SwitchRange> = Vec::new();
+    let mut default = None;
+    for syn in arr {
+        match syn {
+            Syntax::Block(BlockType::Default, _, body) => {
+                if default.is_some() {
+                    return Err(format!("`switch {lhs:?}` has more than one `default` arm"));
+                }
+                default = Some((
+                    inner_interpret(body, state, path, src_files, config)?,
+                    format!("__internal__/case_{:x}", get_hash(body)).into(),
+                ));
+            }
+            Syntax::Block(BlockType::Case, match_value, body) => {
+                // `case 1, 2, 5`
+                let values = match &**match_value {
+                    Syntax::Array(values) => &values[..],
+                    value => std::slice::from_ref(value),
                 };
-        cmd_buf.extend(interpret_if(
-            false,
-            &DataLocation::Ident(switch_var.clone()),
-            Operation::Equal,
-            match_value,
-            inner_interpret(body, state, path, src_files, config)?,
-            &format!("__internal__/case_{:x}", get_hash(body)),
-            state,
-            config,
-        )?);
+                for value in values {
+                    if let Syntax::BinaryOp {
+                        lhs,
+                        operation: Operation::DoubleColon,
+                        rhs,
+                    } = value
+                    {
+                        if let (Syntax::Identifier(name), Syntax::Identifier(variant)) =
+                            (&**lhs, &**rhs)
+                        {
+                            handled
+                                .entry(name.clone())
+                                .or_default()
+                                .insert(variant.clone());
+                        }
+                    }
+                    ranges.push(case_range(value, arms.len(), state)?);
+                }
+                arms.push((
+                    inner_interpret(body, state, path, src_files, config)?,
+                    format!("__internal__/case_{:x}", get_hash(body)).into(),
+                ));
+            }
+            _ => return Err(format!("Expected `case` or `default` statement; got `{syn:?}`")),
+        }
+    }
+    for (name, handled) in handled {
+        let Some(variants) = state.enums.get(&name) else {
+            continue;
+        };
+        let missing = variants
+            .iter()
+            .filter(|(variant, _)| !handled.contains(variant))
+            .map(|(variant, _)| format!("`{name}::{variant}`"))
+            .collect::<Vec<_>>();
+        if !missing.is_empty() && default.is_none() {
+            println!(
+                "\x1b[33mWARN\x1b[0m\t`switch {lhs:?}` doesn't handle {}",
+                missing.join(", ")
+            );
+        }
+    }
+    ranges.sort_unstable();
+    for pair in ranges.windows(2) {
+        if pair[0].1 >= pair[1].0 {
+            return Err(format!(
+                "`switch {lhs:?}` has overlapping cases around `{}`",
+                pair[1].0
+            ));
+        }
     }
+    cmd_buf.extend(switch_dispatch(
+        &ranges,
+        (i32::MIN, i32::MAX),
+        &arms,
+        default.as_ref(),
+        &switch_var,
+        state,
+        config,
+    ));
     Ok(cmd_buf)
 }
 
+/// the most ranges a switch checks one after another before splitting into a decision tree
+const MAX_SWITCH_LEAF: usize = 4;
+
+/// an inclusive range of values and the index of the arm it dispatches to
+type SwitchRange = (i32, i32, usize);
+
+/// get the values matched by a `case`, like `3`, `3..7`, or `State::Idle`
+fn case_range(value: &Syntax, arm: usize, state: &InterRepr) -> SResult<SwitchRange> {
+    match &*resolve_enum(value, state)? {
+        Syntax::Integer(int) => Ok((*int, *int, arm)),
+        Syntax::Range(lower, upper) => {
+            let (lower, upper) = (lower.unwrap_or(i32::MIN), upper.unwrap_or(i32::MAX));
+            if lower > upper {
+                return Err(format!("`case {value:?}` can never match"));
+            }
+            Ok((lower, upper, arm))
+        }
+        _ => Err(format!(
+            "Switch cases must be integers, ranges, or enum variants; got `{value:?}`"
+        )),
+    }
+}
+
+/// dispatch to the arms of a switch whose (sorted) ranges lie within `bounds`
+///
+/// Small switches check each range in turn; larger ones split the ranges in half and recurse
+/// into a function for each half, so a lookup only costs a logarithmic number of checks.
+fn switch_dispatch(
+    ranges: &[SwitchRange],
+    bounds: (i32, i32),
+    arms: &[(VecCmd, RStr)],
+    default: Option<&(VecCmd, RStr)>,
+    switch_var: &RStr,
+    state: &mut InterRepr,
+    config: &Config,
+) -> VecCmd {
+    let matches = |invert, lower, upper| ExecuteOption::IfScoreMatches {
+        invert,
+        target: format!("%{switch_var}").into(),
+        objective: config.dummy_objective.clone(),
+        lower: (lower != i32::MIN).then_some(lower),
+        upper: (upper != i32::MAX).then_some(upper),
+    };
+    let mut cmd_buf = VecCmd::default();
+    if ranges.len() <= MAX_SWITCH_LEAF {
+        for (lower, upper, arm) in ranges {
+            let (body, name) = &arms[*arm];
+            cmd_buf.extend(
+                Command::execute(&[matches(false, *lower, *upper)], body.clone(), name, state)
+                    .into_vec(),
+            );
+        }
+        if let Some((body, name)) = default {
+            // none of the ranges in bounds matched
+            let options = ranges
+                .iter()
+                .map(|(lower, upper, _)| matches(true, *lower, *upper))
+                .collect::<Vec<_>>();
+            cmd_buf.extend(Command::execute(&options, body.clone(), name, state).into_vec());
+        }
+        return cmd_buf;
+    }
+    let mid = ranges.len() / 2;
+    // `ranges` are sorted and disjoint, so everything before `mid` is below `split`
+    let split = ranges[mid].0;
+    for (ranges, bounds) in [
+        (&ranges[..mid], (bounds.0, split - 1)),
+        (&ranges[mid..], (split, bounds.1)),
+    ] {
+        let inner = switch_dispatch(ranges, bounds, arms, default, switch_var, state, config);
+        cmd_buf.extend(
+            Command::execute(
+                &[matches(false, bounds.0, bounds.1)],
+                inner,
+                &format!("{switch_var}_{:x}", get_hash(&bounds)),
+                state,
+            )
+            .into_vec(),
+        );
+    }
+    cmd_buf
+}
+
 /// # Panics
-/// If passed a `BlockType` other than `For`, `Until`, `DoWhile`, `While`, or `DoUntil`
+/// If passed a `BlockType` other than `Until`, `DoWhile`, `While`, or `DoUntil`
 #[allow(clippy::too_many_arguments)]
 fn loop_block(
     block_type: BlockType,
-    left: DataLocation,
+    left: &DataLocation,
     op: Operation,
     right: &Syntax,
     block: &Syntax,
@@ -178,20 +482,14 @@ fn loop_block(
     config: &Config,
 ) -> SResult<VecCmd> {
     let invert = match block_type {
-        BlockType::For | BlockType::DoWhile | BlockType::While => false,
+        BlockType::DoWhile | BlockType::While => false,
         BlockType::DoUntil | BlockType::Until => true,
         _ => unreachable!(),
     };
     let fn_name: RStr = format!("__internal__/{:x}", get_hash(block)).into();
-    // for _ in .. => replace `_` with hash
-    let left = if block_type == BlockType::For && left == DataLocation::Ident("_".into()) {
-        DataLocation::Ident(format!("{:x}", get_hash(block)).into())
-    } else {
-        left
-    };
     let binding = interpret_if(
         invert,
-        &left,
+        left,
         op,
         right,
         vec![Command::Function(fn_name.clone())].into(),
@@ -203,33 +501,136 @@ fn loop_block(
     let mut body = inner_interpret(block, state, path, src_files, config)?;
     // this is the code that runs to enter the loop
     let mut initial = VecCmd::default();
-    if block_type == BlockType::For {
-        // reset value at start of for loop
-        let &Syntax::Range(start, _) = right else {
-                return Err(format!("Expected `for {{variable}} in {{range}}`; got `{right:?}`"))
+    // don't perform the initial check for do-while or do-until loops
+    if matches!(block_type, BlockType::DoWhile | BlockType::DoUntil) {
+        initial.push(Command::Function(fn_name.clone()).into());
+    } else {
+        initial.extend(binding.clone());
+    }
+    // always check to restart loop at the end
+    body.extend(binding);
+    state.functions.insert(fn_name, body);
+    Ok(initial)
+}
+
+/// the step of a `for` loop that doesn't give one, counting down if the range is backwards
+fn default_step(start: &Syntax, end: &Syntax, range: &Syntax) -> SResult<i32> {
+    match (start, end) {
+        (Syntax::Integer(start), Syntax::Integer(end)) if start > end => Ok(-1),
+        (Syntax::Integer(_), _) => Ok(1),
+        // the direction isn't known until the loop runs
+        _ => Err(format!(
+            "`for` loop over `{range:?}` starts at a score, so it needs a `step`, like `step 1` or `step -1`"
+        )),
+    }
+}
+
+/// Handle a `for` loop over a range, like `for i in 0..<n step 2 {...}`
+///
+/// Bounds that aren't literals are evaluated once, before the loop starts
+fn for_block(
+    left: DataLocation,
+    right: &Syntax,
+    block: &Syntax,
+    state: &mut InterRepr,
+    path: &Path,
+    src_files: &mut BTreeSet<PathBuf>,
+    config: &Config,
+) -> SResult<VecCmd> {
+    let (start, end, inclusive, step) = match right {
+        Syntax::Range(start, Some(end)) => (Syntax::Integer(start.unwrap_or(0)), Syntax::Integer(*end), true, None),
+        Syntax::ForRange {
+            start,
+            end,
+            inclusive,
+            step,
+        } => ((**start).clone(), (**end).clone(), *inclusive, *step),
+        _ => return Err(format!("Expected `for {{variable}} in {{range}}`; got `{right:?}`")),
+    };
+    let step = match step {
+        Some(step) => step,
+        None => default_step(&start, &end, right)?,
+    };
+    if step == 0 {
+        return Err(String::from("`for` loop step can't be 0"));
+    }
+    let fn_name: RStr = format!("__internal__/{:x}", get_hash(block)).into();
+    // for _ in .. => replace `_` with hash
+    let left = if left == DataLocation::Ident("_".into()) {
+        DataLocation::Ident(format!("{:x}", get_hash(block)).into())
+    } else {
+        left
+    };
+    // this is the code that runs to enter the loop
+    let mut initial = if let Syntax::Integer(start) = start {
+        // `= 0` would reset the score, which fails the first check
+        let objective = left.stringify_scoreboard_objective(config)?;
+        state.use_objective(&objective);
+        vec![Command::score_init(&left.stringify_scoreboard_target()?, &objective, start)].into()
+    } else {
+        operation(&left.clone().into(), Operation::Equal, &start, state, config)?
+    };
+    let (op, bound) = match (&start, end) {
+        (_, Syntax::Integer(end)) => {
+            let end = if inclusive { end } else { end - step.signum() };
+            let start = if let Syntax::Integer(start) = start {
+                Some(start)
+            } else {
+                None
             };
-        initial.push(
-            Command::ScoreSet {
-                target: left.stringify_scoreboard_target()?,
-                objective: left.stringify_scoreboard_objective(config)?,
-                value: start.unwrap_or(0),
-            }
-            .into(),
-        );
-        body.push(
-            Command::ScoreAdd {
-                target: left.stringify_scoreboard_target()?,
-                objective: left.stringify_scoreboard_objective(config)?,
-                value: 1,
+            let range = if step > 0 {
+                (start, Some(end))
+            } else {
+                (Some(end), start)
+            };
+            if let (Some(lower), Some(upper)) = range {
+                if lower > upper {
+                    println!("\x1b[33mWARN\x1b[0m\t`for` loop over `{right:?}` never runs");
+                    return Ok(VecCmd::default());
+                }
             }
-            .into(),
-        );
-    }
-    // don't perform the initial check for do-while, do-until, or for loops
-    if matches!(
-        block_type,
-        BlockType::DoWhile | BlockType::DoUntil | BlockType::For
-    ) {
+            (Operation::In, Syntax::Range(range.0, range.1))
+        }
+        (_, end) => {
+            let end_var: RStr = format!("__for_end__{:x}", get_hash(block)).into();
+            initial.extend(operation(
+                &Syntax::Identifier(end_var.clone()),
+                Operation::Equal,
+                &end,
+                state,
+                config,
+            )?);
+            let op = match (step > 0, inclusive) {
+                (true, true) => Operation::LCaretEq,
+                (true, false) => Operation::LCaret,
+                (false, true) => Operation::RCaretEq,
+                (false, false) => Operation::RCaret,
+            };
+            (op, Syntax::Identifier(end_var))
+        }
+    };
+    let binding = interpret_if(
+        false,
+        &left,
+        op,
+        &bound,
+        vec![Command::Function(fn_name.clone())].into(),
+        "",
+        state,
+        config,
+    )?;
+    // this is the code that runs on each loop
+    let mut body = inner_interpret(block, state, path, src_files, config)?;
+    body.push(
+        Command::ScoreAdd {
+            target: left.stringify_scoreboard_target()?,
+            objective: left.stringify_scoreboard_objective(config)?,
+            value: step,
+        }
+        .into(),
+    );
+    // a range between two literals is known to run at least once
+    if matches!((&start, op), (Syntax::Integer(_), Operation::In)) {
         initial.push(Command::Function(fn_name.clone()).into());
     } else {
         initial.extend(binding.clone());
@@ -246,7 +647,7 @@ fn loop_block(
     clippy::too_many_arguments,
     clippy::manual_let_else
 )]
-fn interpret_if(
+pub(super) fn interpret_if(
     invert: bool,
     left: &DataLocation,
     op: Operation,
@@ -263,6 +664,7 @@ fn interpret_if(
         );
         return Ok(VecCmd::default());
     }
+    let right = &*resolve_enum(right, state)?;
     let mut setter = None;
     let (target_player, target_objective) = if let (Ok(target_player), Ok(target_objective)) = (
         left.stringify_scoreboard_target(),
@@ -281,9 +683,9 @@ fn interpret_if(
     };
     let options = match right {
         Syntax::Identifier(_) | Syntax::BinaryOp { .. } | Syntax::SelectorColon(_, _) => {
-            let (source, source_objective, mut commands) = match right {
+            let (source, source_objective, _commands) = match right {
                 Syntax::Identifier(ident) => (
-                    ident.clone(),
+                    format!("%{ident}").into(),
                     config.dummy_objective.clone(),
                     VecCmd::default(),
                 ),
@@ -292,7 +694,7 @@ fn interpret_if(
                     operation: Operation::Colon,
                     rhs: right,
                 } => {
-                    let (mut commands, left) = get_data_location(left)?;
+                    let (commands, left) = get_data_location(left)?;
                     match &**right {
                         Syntax::Identifier(ident) => {
                             (left.stringify_scoreboard_target()?, ident.clone(), commands)
@@ -311,11 +713,7 @@ fn interpret_if(
                 ),
                 _ => return Err(format!("Can't compare to `{right:?}`")),
             };
-            if !state.objectives.contains_key(&source_objective) {
-                state
-                    .objectives
-                    .insert(source_objective.clone(), config.dummy_objective.clone());
-            }
+            state.use_objective(&source_objective);
             match op {
                 // x = var
                 Operation::LCaret
@@ -375,7 +773,7 @@ fn interpret_if(
                 return Err(format!(
                     "Can't check if `{{...}} {op} {{range}}`. Did you mean `{{...}} in {{range}}`?"
                 ));
-            };
+            }
             vec![ExecuteOption::IfScoreMatches {
                 invert,
                 target: target_player,
@@ -390,7 +788,7 @@ fn interpret_if(
     if let Some(setter) = setter {
         ret_val.map_with(
             |cmds, setter| {
-                cmds.splice(0..0, setter.into_iter());
+                cmds.splice(0..0, setter);
             },
             setter,
         );
@@ -460,6 +858,21 @@ fn ident_block(
     {
         return Err(format!("Invalid `on` identifier: `{ident}`; expected `attacker`, `controller`, `leasher`, `origin`, `owner`, `passengers`, `target`, or `vehicle`"));
     }
+    if block_type == BlockType::Align
+        && (ident.is_empty()
+            || !ident.chars().all(|axis| matches!(axis, 'x' | 'y' | 'z'))
+            || (1..ident.len()).any(|i| ident[i..].contains(&ident[i - 1..i])))
+    {
+        return Err(format!("Invalid `align` axes: `{ident}`; expected some of `x`, `y`, and `z`, like `xz`"));
+    }
+    if block_type == BlockType::PositionedOver
+        && !matches!(
+            &*ident,
+            "world_surface" | "motion_blocking" | "motion_blocking_no_leaves" | "ocean_floor"
+        )
+    {
+        return Err(format!("Invalid heightmap: `{ident}`; expected `world_surface`, `motion_blocking`, `motion_blocking_no_leaves`, or `ocean_floor`"));
+    }
 
     let content = inner_interpret(body, state, path, src_files, config)?;
 
@@ -476,11 +889,113 @@ fn ident_block(
             ExecuteOption::Anchored(ident),
             format!("__internal__/anchored_{:x}", get_hash(body)),
         ),
+        BlockType::In => (
+            ExecuteOption::In(ident),
+            format!("__internal__/in_{:x}", get_hash(body)),
+        ),
+        BlockType::Align => (
+            ExecuteOption::Align(ident),
+            format!("__internal__/align_{:x}", get_hash(body)),
+        ),
+        BlockType::PositionedOver => (
+            ExecuteOption::PositionedOver(ident),
+            format!("__internal__/positionedover_{:x}", get_hash(body)),
+        ),
         _ => unreachable!(),
     };
     Ok(Command::execute(&[options], content, &hash, state).into_vec())
 }
 
+/// summon an entity with a `summon` command, then run the body as and at it
+#[allow(clippy::too_many_arguments)]
+fn summon_block(
+    entity: &Syntax,
+    pos: &Syntax,
+    properties: &Syntax,
+    body: &Syntax,
+    state: &mut InterRepr,
+    path: &Path,
+    src_files: &mut BTreeSet<PathBuf>,
+    config: &Config,
+) -> SResult<VecCmd> {
+    let entity = RStr::try_from(entity)
+        .map_err(|_| format!("Expected an entity type to summon; got `{entity:?}`"))?;
+    let pos = match pos {
+        Syntax::Unit => Coordinate::here(),
+        pos => Coordinate::try_from(pos)?,
+    };
+    let mut nbt = BTreeMap::new();
+    let mut tags = Vec::new();
+    match properties {
+        Syntax::Object(obj) => {
+            for (k, v) in obj {
+                match (&**k, v) {
+                    ("nbt", Syntax::Object(_)) => nbt = Nbt::try_from(v)?.get_obj()?.clone(),
+                    ("tags", Syntax::Array(arr)) => {
+                        for tag in arr.iter() {
+                            tags.push(Nbt::from(String::try_from(tag)?));
+                        }
+                    }
+                    ("tags", tag @ (Syntax::Identifier(_) | Syntax::String(_))) => {
+                        tags.push(Nbt::from(String::try_from(tag)?));
+                    }
+                    ("nbt" | "tags", other) => {
+                        return Err(format!("Invalid value for summon `{k}`: `{other:?}`"))
+                    }
+                    (other, _) => return Err(format!("Invalid summon property: `{other}`; expected `nbt` or `tags`")),
+                }
+            }
+        }
+        Syntax::Unit => {}
+        other => return Err(format!("Expected summon properties like `{{ nbt: {{...}}, tags: [...] }}`; got `{other:?}`")),
+    }
+    let inner = inner_interpret(body, state, path, src_files, config)?;
+    // tag the new entity so the body can find it, then take the tag off again
+    let hash = get_hash(&(&entity, &pos, properties, body));
+    let marker: RStr = format!("__summoned_{hash:x}").into();
+    if !inner.is_empty() {
+        tags.push(Nbt::from(marker.clone()));
+    }
+    if !tags.is_empty() {
+        match nbt.get_mut("Tags") {
+            Some(Nbt::Array(existing)) => existing.extend(tags),
+            Some(other) => return Err(format!("Summon NBT `Tags` must be a list; got `{other}`")),
+            None => {
+                nbt.insert("Tags".into(), Nbt::Array(tags));
+            }
+        }
+    }
+    let mut cmd_buf: VecCmd = vec![Command::Summon {
+        entity,
+        pos,
+        nbt: (!nbt.is_empty()).then_some(Nbt::Object(nbt)),
+    }]
+    .into();
+    if inner.is_empty() {
+        return Ok(cmd_buf);
+    }
+    let mut content: VecCmd = vec![Command::Tag {
+        target: Selector::s(),
+        add: false,
+        tag: marker.clone(),
+    }]
+    .into();
+    content.extend(inner);
+    let selector = Selector::e()
+        .with_property("tag", marker.to_string())
+        .with_property("limit", String::from("1"));
+    cmd_buf.extend(Command::execute(
+        &[
+            ExecuteOption::As(selector),
+            ExecuteOption::At(Selector::s()),
+        ],
+        content,
+        &format!("__internal__/summon_{hash:x}"),
+        state,
+    ).into_vec());
+    Ok(cmd_buf)
+}
+
 fn coord_block(
     block_type: BlockType,
     coord: Coordinate,
@@ -494,9 +1009,11 @@ fn coord_block(
     match block_type {
         BlockType::Facing => opts.push(ExecuteOption::FacingPos(coord)),
         BlockType::Positioned => {
-            println!(
-                "\x1b[33mWARN\x1b[0m\t`positioned (~ ~ ~) {{ ... }}`; This is a non-operation."
-            );
+            if coord == Coordinate::here() {
+                println!(
+                    "\x1b[33mWARN\x1b[0m\t`positioned (~ ~ ~) {{ ... }}`; This is a non-operation."
+                );
+            }
             opts.push(ExecuteOption::Positioned(coord));
         }
         _ => return Err(format!("`{block_type:?}` block does not take a coordinate")),
@@ -573,14 +1090,12 @@ fn async_block(
     for cmd in arr.iter() {
         if let Syntax::Annotation(id, body) = cmd {
             if &**id == "delay" {
-                let Syntax::Integer(time) = &**body else {
-                            return Err(format!("Expected an integer for delay; got `{body:?}`"))
-                        };
+                let time = super::annotations::ticks(body)?;
                 let next_func: RStr = format!("__async__/{:x}", get_hash(&func)).into();
                 command_buf.push(
                     Command::Schedule {
                         func: next_func.clone(),
-                        time: *time,
+                        time,
                         replace: false,
                     }
                     .into(),
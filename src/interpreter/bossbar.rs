@@ -0,0 +1,221 @@
+use super::{block::interpret_if, get_data_location, selector_block::tellraw_component, InterRepr};
+use crate::{types::prelude::*, Config};
+
+/// declare a bossbar like `@bossbar fight { name: "Boss", color: red, max: 200, players: @a }`
+pub(super) fn declaration(
+    properties: &Syntax,
+    state: &mut InterRepr,
+    config: &Config,
+) -> SResult<()> {
+    let Syntax::BinaryOp { lhs, operation: Operation::Colon, rhs } = properties else {
+        return Err(format!("Expected `@bossbar {{id}} {{...}}`; got `@bossbar {properties:?}`"))
+    };
+    let Syntax::Identifier(id) = &**lhs else {
+        return Err(format!("Bossbar id must be an identifier; got `{lhs:?}`"))
+    };
+    if state.bossbars.contains_key(id) {
+        return Err(format!("Bossbar `{id}` is already declared"));
+    }
+    let mut name = Nbt::String(id.clone()).to_json();
+    let mut settings = Vec::new();
+    match &**rhs {
+        Syntax::Object(obj) => {
+            for (k, v) in obj {
+                if &**k == "name" {
+                    name = tellraw_component(v, config)?.to_json();
+                } else {
+                    settings.push(set(id, k, v, config)?);
+                }
+            }
+        }
+        Syntax::Unit => {}
+        other => return Err(format!("Expected an object for bossbar `{id}`; got `{other:?}`")),
+    }
+    let mut commands = vec![Command::BossbarAdd {
+        id: id.clone(),
+        name: name.into(),
+    }];
+    commands.extend(settings);
+    state.bossbars.insert(id.clone(), commands);
+    Ok(())
+}
+
+/// get the command that sets a bossbar property to a constant, like `color: red`
+fn set(id: &RStr, property: &str, value: &Syntax, config: &Config) -> SResult<Command> {
+    let value: RStr = match (property, value) {
+        ("name", _) => tellraw_component(value, config)?.to_json().into(),
+        ("color", Syntax::Identifier(color))
+            if matches!(
+                &**color,
+                "blue" | "green" | "pink" | "purple" | "red" | "white" | "yellow"
+            ) =>
+        {
+            color.clone()
+        }
+        ("style", Syntax::Identifier(style))
+            if matches!(
+                &**style,
+                "progress" | "notched_6" | "notched_10" | "notched_12" | "notched_20"
+            ) =>
+        {
+            style.clone()
+        }
+        ("value" | "max", Syntax::Integer(int)) => format!("{int}").into(),
+        ("visible", Syntax::Identifier(visible)) if matches!(&**visible, "true" | "false") => {
+            visible.clone()
+        }
+        ("players", Syntax::Selector(selector)) => selector.stringify()?.to_string().into(),
+        ("color" | "style" | "value" | "max" | "visible" | "players", _) => return Err(format!("Invalid value for `bossbar::{id}.{property}`: `{value:?}`")),
+        _ => return Err(format!("Invalid bossbar property: `{property}`")),
+    };
+    Ok(Command::BossbarSet {
+        id: id.clone(),
+        property: property.into(),
+        value,
+    })
+}
+
+/// get the command that reads a bossbar property, like `x = bossbar::fight.value`
+pub(super) fn get(id: RStr, property: RStr, state: &InterRepr) -> SResult<Command> {
+    if !state.bossbars.contains_key(&id) {
+        return Err(format!("Unknown bossbar `{id}`; bossbars must be declared with `@bossbar` before they're used"));
+    }
+    if !matches!(&*property, "value" | "max" | "visible" | "players") {
+        return Err(format!("Can't get `{property}` of a bossbar; expected `value`, `max`, `visible`, or `players`"));
+    }
+    Ok(Command::BossbarGet { id, property })
+}
+
+/// interpret an assignment to a bossbar property, like `bossbar::fight.value = @s:hp`
+///
+/// `visible` can also be given a condition, like a selector or comparison, and follows it
+#[allow(clippy::too_many_lines)]
+pub(super) fn operation(
+    id: &RStr,
+    property: &str,
+    op: Operation,
+    rhs: &Syntax,
+    state: &mut InterRepr,
+    config: &Config,
+) -> SResult<VecCmd> {
+    if !state.bossbars.contains_key(id) {
+        return Err(format!("Unknown bossbar `{id}`; bossbars must be declared with `@bossbar` before they're used"));
+    }
+    if op != Operation::Equal {
+        return Err(format!("Bossbar properties can only be assigned with `=`; got `bossbar::{id}.{property} {op} {rhs:?}`"));
+    }
+    // `(hp > 0)`
+    let rhs = match rhs {
+        Syntax::Array(arr) if arr.len() == 1 => &arr[0],
+        rhs => rhs,
+    };
+    let visible = |value: &str| -> VecCmd {
+        vec![Command::BossbarSet {
+            id: id.clone(),
+            property: "visible".into(),
+            value: value.into(),
+        }]
+        .into()
+    };
+    match (property, rhs) {
+        // constants
+        ("name" | "color" | "style", _)
+        | ("value" | "max", Syntax::Integer(_))
+        | ("players", Syntax::Selector(_)) => Ok(vec![set(id, property, rhs, config)?].into()),
+        ("visible", Syntax::Identifier(visible)) if matches!(&**visible, "true" | "false") => {
+            Ok(vec![set(id, property, rhs, config)?].into())
+        }
+        // shown while the entity exists
+        ("visible", Syntax::Selector(selector)) => {
+            let selector = selector.stringify()?;
+            let mut cmd_buf = VecCmd::default();
+            for (invert, value) in [(false, "true"), (true, "false")] {
+                cmd_buf.extend(
+                    Command::execute(
+                        &[ExecuteOption::IfEntity {
+                            invert,
+                            selector: selector.clone(),
+                        }],
+                        visible(value),
+                        "",
+                        state,
+                    )
+                    .into_vec(),
+                );
+            }
+            Ok(cmd_buf)
+        }
+        // shown while the comparison holds
+        (
+            "visible",
+            Syntax::BinaryOp {
+                lhs: left,
+                operation: condition,
+                rhs: right,
+            },
+        ) if *condition != Operation::DoubleColon => {
+            let (mut cmd_buf, left) = get_data_location(left)?;
+            for (invert, value) in [(false, "true"), (true, "false")] {
+                cmd_buf.extend(interpret_if(
+                    invert,
+                    &left,
+                    *condition,
+                    right,
+                    visible(value),
+                    "",
+                    state,
+                    config,
+                )?);
+            }
+            Ok(cmd_buf)
+        }
+        // shown while the score isn't zero
+        ("visible", _) => {
+            let (mut cmd_buf, left) = get_data_location(rhs)?;
+            for (condition, value) in [(Operation::BangEq, "true"), (Operation::Equal, "false")] {
+                cmd_buf.extend(interpret_if(
+                    false,
+                    &left,
+                    condition,
+                    &Syntax::Integer(0),
+                    visible(value),
+                    "",
+                    state,
+                    config,
+                )?);
+            }
+            Ok(cmd_buf)
+        }
+        // stored from a score or NBT
+        ("value" | "max", _) => {
+            let get = match get_data_location(rhs)?.1 {
+                DataLocation::SelectorNbt(selector, nbt) => {
+                    Command::DataGet(NbtLocation::Entity(selector.stringify()?, nbt))
+                }
+                DataLocation::NbtStorage(nbt) => Command::DataGet(NbtLocation::Storage(nbt)),
+                DataLocation::Bossbar(id, property) => get(id, property, state)?,
+                DataLocation::SelectorDoubleColon(_, _) => {
+                    return Err(format!("Can't assign `{rhs:?}` to a bossbar"))
+                }
+                score => {
+                    let objective = score.stringify_scoreboard_objective(config)?;
+                    state.use_objective(&objective);
+                    Command::ScoreGet {
+                        target: score.stringify_scoreboard_target()?,
+                        objective,
+                    }
+                }
+            };
+            Ok(vec![Command::Execute {
+                options: vec![ExecuteOption::StoreBossbar {
+                    id: id.clone(),
+                    max: property == "max",
+                    is_success: false,
+                }],
+                cmd: Box::new(get),
+            }]
+            .into())
+        }
+        _ => Err(format!("Can't assign `{rhs:?}` to `bossbar::{id}.{property}`")),
+    }
+}
@@ -1,4 +1,4 @@
-use super::{get_data_location, InterRepr};
+use super::{get_data_location, resolve_enum, InterRepr};
 use crate::{types::prelude::*, Config};
 
 /// interpret an operation, like `x += 1`
@@ -10,7 +10,16 @@ pub(super) fn operation(
     config: &Config,
 ) -> SResult<VecCmd> {
     let (mut commands, lhs) = get_data_location(lhs)?;
+    let rhs = &*resolve_enum(rhs, state)?;
     commands.extend(match (lhs, op, rhs) {
+        // @s::attr.generic.max_health
+        (DataLocation::SelectorDoubleColon(sel, ident), _, _) if ident.starts_with("attr.") => {
+            super::attribute::operation(&sel, &ident["attr.".len()..], op, rhs, config)
+        }
+        // @s::y
+        (DataLocation::SelectorDoubleColon(sel, ident), _, _) if super::property::is_property(&ident) => {
+            super::property::operation(&sel, &ident, op, rhs, state, config)
+        }
         // @s::xp
         (DataLocation::SelectorDoubleColon(sel, ident), _, _) => {
             double_colon(&sel, &ident, op, rhs)
@@ -26,6 +35,10 @@ pub(super) fn operation(
         (DataLocation::NbtStorage(nbt), _, _) => {
             nbt_op(NbtLocation::Storage(nbt), op, rhs, state, config)
         }
+        // bossbar::fight.value
+        (DataLocation::Bossbar(id, property), _, _) => {
+            super::bossbar::operation(&id, &property, op, rhs, state, config)
+        }
         // x | @s:score | var:x
         (lhs, op, rhs) => simple_operation(
             lhs.stringify_scoreboard_target()?,
@@ -49,13 +62,52 @@ fn simple_operation(
     state: &mut InterRepr,
     config: &Config,
 ) -> SResult<VecCmd> {
-    if !state.objectives.contains_key(&target_objective) {
-        state
-            .objectives
-            .insert(target_objective.clone(), config.dummy_objective.clone());
-    }
+    state.use_objective(&target_objective);
     match (op, syn) {
         (_, Syntax::Integer(value)) => integer_operation(target_name, target_objective, op, *value, state, config),
+        // x = @s::attr.generic.max_health | x = @s::y
+        (_, Syntax::SelectorDoubleColon(sel, ident)) if ident.starts_with("attr.") || super::property::is_property(ident) => {
+            // fixed-point operations read the value already scaled
+            let (op, scale) = match op {
+                Operation::FpEq => (Operation::Equal, config.fixed_point_accuracy),
+                Operation::FpAddEq => (Operation::AddEq, config.fixed_point_accuracy),
+                Operation::FpSubEq => (Operation::SubEq, config.fixed_point_accuracy),
+                Operation::FpMulEq | Operation::FpDivEq => (op, config.fixed_point_accuracy),
+                op => (op, 1),
+            };
+            let get = if let Some(attribute) = ident.strip_prefix("attr.") {
+                super::attribute::get(sel, attribute, scale)?
+            } else {
+                super::property::get(sel, ident, scale)?.into()
+            };
+            let (attr_target, attr_objective) = if op == Operation::Equal {
+                (target_name.clone(), target_objective.clone())
+            } else {
+                ("%__attr__".into(), config.dummy_objective.clone())
+            };
+            let mut vec: VecCmd = get
+                .map(|get| Command::Execute {
+                    options: vec![ExecuteOption::StoreScore {
+                        target: attr_target.clone(),
+                        objective: attr_objective.clone(),
+                        is_success: false,
+                    }],
+                    cmd: Box::new(get),
+                })
+                .into_vec();
+            if op != Operation::Equal {
+                vec.extend(score_operation(
+                    target_name,
+                    target_objective,
+                    op,
+                    "%__attr__".into(),
+                    config.dummy_objective.clone(),
+                    state,
+                    config
+                )?);
+            }
+            Ok(vec)
+        }
         (_, Syntax::SelectorDoubleColon(sel, ident)) => {
             let ident = &**ident;
             let levels = if ident == "lvl" || ident == "level" {
@@ -64,7 +116,7 @@ fn simple_operation(
                 false
             } else {
                 return Err(format!(
-                    "A selector can only be `::` indexed with `lvl` or `xp`, not `{ident}`"
+                    "A selector can only be `::` indexed with `lvl`, `xp`, `attr.<attribute>`, or a property like `y`, not `{ident}`"
                 ));
             };
             let (xp_target, xp_objective) = if op == Operation::Equal {
@@ -98,22 +150,30 @@ fn simple_operation(
             }
             Ok(vec)
         }
-        (Operation::Equal, Syntax::SelectorNbt(selector, nbt)) => Ok(vec![Command::Execute {
-            options: vec![ExecuteOption::StoreScore {
-                target: target_name,
-                objective: target_objective,
-                is_success: false,
-            }],
-            cmd: Box::new(Command::DataGet(NbtLocation::Entity(selector.stringify()?, nbt.clone()))),
-        }].into()),
-        (op, Syntax::SelectorNbt(selector, nbt)) => {
+        (op, Syntax::SelectorNbt(_, _) | Syntax::NbtStorage(_) | Syntax::BinaryOp { operation: Operation::DoubleColon, .. }) => {
+            let get = match DataLocation::try_from(syn.clone()) {
+                Ok(DataLocation::SelectorNbt(selector, nbt)) => Command::DataGet(NbtLocation::Entity(selector.stringify()?, nbt)),
+                Ok(DataLocation::NbtStorage(nbt)) => Command::DataGet(NbtLocation::Storage(nbt)),
+                Ok(DataLocation::Bossbar(id, property)) => super::bossbar::get(id, property, state)?,
+                _ => return Err(format!("Can't get a value from `{syn:?}`")),
+            };
+            if op == Operation::Equal {
+                return Ok(vec![Command::Execute {
+                    options: vec![ExecuteOption::StoreScore {
+                        target: target_name,
+                        objective: target_objective,
+                        is_success: false,
+                    }],
+                    cmd: Box::new(get),
+                }].into());
+            }
             let mut cmd_buf: VecCmd = vec![Command::Execute {
                 options: vec![ExecuteOption::StoreScore {
                     target: "%".into(),
                     objective: config.dummy_objective.clone(),
                     is_success: false
                 }],
-                cmd: Box::new(Command::DataGet(NbtLocation::Entity(selector.stringify()?, nbt.clone()))),
+                cmd: Box::new(get),
             }].into();
             cmd_buf.extend(simple_operation(
                 target_name,
@@ -221,16 +281,8 @@ pub(super) fn score_operation(
     state: &mut InterRepr,
     config: &Config,
 ) -> SResult<VecCmd> {
-    if !state.objectives.contains_key(&target_objective) {
-        state
-            .objectives
-            .insert(target_objective.clone(), config.dummy_objective.clone());
-    }
-    if target_objective != src_objective && !state.objectives.contains_key(&target_objective) {
-        state
-            .objectives
-            .insert(target_objective.clone(), config.dummy_objective.clone());
-    }
+    state.use_objective(&target_objective);
+    state.use_objective(&src_objective);
     match op {
         Operation::AddEq | Operation::SubEq | Operation::MulEq | Operation::DivEq | Operation::ModEq | Operation::LCaret | Operation::RCaret | Operation::Swap | Operation::Equal => {
             Ok(vec![Command::ScoreOperation { target: target_name, target_objective, operation: op, source: src_name, source_objective: src_objective }].into())
@@ -363,7 +415,7 @@ fn double_colon(
         "xp" | "experience" => false,
         _ => {
             return Err(format!(
-                "A selector can only be `::` indexed with `lvl` or `xp`, not `{ident}`"
+                "A selector can only be `::` indexed with `lvl`, `xp`, `attr.<attribute>`, or a property like `y`, not `{ident}`"
             ))
         }
     };
@@ -437,6 +489,7 @@ fn nbt_op(
                 &[ExecuteOption::StoreNBT {
                     location: lhs,
                     is_success: false,
+                    data_type: "float".into(),
                     scale: 1.0,
                 }],
                 cmd.into(),
@@ -461,6 +514,7 @@ fn nbt_op(
                 &[ExecuteOption::StoreNBT {
                     location: lhs,
                     is_success: false,
+                    data_type: "float".into(),
                     scale: config.fixed_point_accuracy as f32,
                 }],
                 vec![Command::ScoreGet {
@@ -0,0 +1,222 @@
+use super::InterRepr;
+use crate::{types::prelude::*, Config};
+
+/// an entity's position, rotation, health, food, or motion, like `@s::y` or `@s::health`
+///
+/// a property can give its own fixed-point scale for scores, like `@s::x(1000)`
+struct Property {
+    /// where the value lives in the entity's NBT
+    path: NbtPath,
+    /// the NBT type it's stored as
+    data_type: &'static str,
+    /// how many score points make one unit of the property, if given
+    scale: Option<i32>,
+}
+
+impl Property {
+    fn new(name: &str) -> Option<Self> {
+        let (name, scale) = match name.strip_suffix(')').and_then(|name| name.split_once('(')) {
+            Some((name, scale)) => (name, Some(scale.parse().ok()?)),
+            None => (name, None),
+        };
+        let (tag, index, data_type) = match name {
+            "x" => ("Pos", Some(0), "double"),
+            "y" => ("Pos", Some(1), "double"),
+            "z" => ("Pos", Some(2), "double"),
+            "yaw" => ("Rotation", Some(0), "float"),
+            "pitch" => ("Rotation", Some(1), "float"),
+            "health" => ("Health", None, "float"),
+            "food" => ("foodLevel", None, "int"),
+            "motion_x" => ("Motion", Some(0), "double"),
+            "motion_y" => ("Motion", Some(1), "double"),
+            "motion_z" => ("Motion", Some(2), "double"),
+            _ => return None,
+        };
+        let mut path = vec![NbtPathPart::Ident(tag.into())];
+        path.extend(index.map(NbtPathPart::Index));
+        Some(Self { path, data_type, scale })
+    }
+}
+
+/// whether `@s::{name}` is one of the pseudo-properties
+pub(super) fn is_property(name: &str) -> bool {
+    Property::new(name).is_some()
+}
+
+/// get the command that reads a pseudo-property, like `x = @s::y`
+pub(super) fn get(selector: &Selector<Syntax>, name: &str, scale: i32) -> SResult<Command> {
+    let Some(property) = Property::new(name) else {
+        return Err(format!("Unknown property `::{name}`"))
+    };
+    let target = selector.stringify()?;
+    if !is_single(&target) {
+        return Err(format!("Can't read `{target}::{name}` from more than one entity; add `limit=1`"));
+    }
+    Ok(Command::DataGetScaled {
+        target: NbtLocation::Entity(target, property.path),
+        scale: property.scale.unwrap_or(scale),
+    })
+}
+
+/// interpret an assignment to a pseudo-property, like `@s::y += 10` or `@e[tag=ball]::motion_y .= speed`
+///
+/// positions and rotations are set with `tp`, so they work on players too; everything else goes through NBT
+pub(super) fn operation(
+    selector: &Selector<Syntax>,
+    name: &str,
+    op: Operation,
+    rhs: &Syntax,
+    state: &mut InterRepr,
+    config: &Config,
+) -> SResult<VecCmd> {
+    let Some(property) = Property::new(name) else {
+        return Err(format!("Unknown property `::{name}`"))
+    };
+    // `property` keeps the scale, if there is one
+    let (scaled_name, name) = (name, name.split('(').next().unwrap_or(name));
+    if name == "food" {
+        return Err(String::from("`::food` can only be read; vanilla doesn't allow changing a player's food level"));
+    }
+    let target = selector.stringify()?;
+    #[allow(clippy::cast_precision_loss)]
+    let constant = match rhs {
+        Syntax::Integer(int) => Some(*int as f32),
+        Syntax::Float(float) => Some(*float),
+        _ => None,
+    };
+    match (name, op, constant) {
+        // teleport to an absolute or relative position
+        ("x" | "y" | "z" | "yaw" | "pitch", Operation::Equal | Operation::AddEq | Operation::SubEq, Some(value)) => {
+            let value = if op == Operation::SubEq { -value } else { value };
+            let relative = op != Operation::Equal;
+            let axis = |axis: &str| {
+                if axis != name {
+                    String::from("~")
+                } else if relative {
+                    format!("~{value}")
+                } else {
+                    value.to_string()
+                }
+            };
+            let tp: Command = if matches!(name, "yaw" | "pitch") {
+                Command::Raw(format!("tp @s ~ ~ ~ {} {}", axis("yaw"), axis("pitch")).into())
+            } else {
+                Command::Raw(format!("tp @s {} {} {}", axis("x"), axis("y"), axis("z")).into())
+            };
+            // relative coordinates are relative to the entity, and each entity is teleported separately
+            let mut options = Vec::new();
+            if target != Selector::s() {
+                options.push(ExecuteOption::As(target));
+            }
+            options.push(ExecuteOption::At(Selector::s()));
+            Ok(vec![Command::Execute {
+                options,
+                cmd: Box::new(tp),
+            }]
+            .into())
+        }
+        (_, Operation::Equal | Operation::FpEq, Some(value)) => {
+            if is_player(&target) {
+                return Err(format!("Can't set `{target}::{name}`; vanilla doesn't allow changing players' NBT"));
+            }
+            let value = match property.data_type {
+                "double" => format!("{value}d"),
+                _ => format!("{value}f"),
+            };
+            // `data modify entity` only takes one entity, so set each one as itself
+            let (options, target) = if is_single(&target) {
+                (Vec::new(), target)
+            } else {
+                (vec![ExecuteOption::As(target)], Selector::s())
+            };
+            let set = Command::DataSetValue {
+                target: NbtLocation::Entity(target, property.path),
+                value: value.into(),
+            };
+            if options.is_empty() {
+                Ok(vec![set].into())
+            } else {
+                Ok(vec![Command::Execute {
+                    options,
+                    cmd: Box::new(set),
+                }]
+                .into())
+            }
+        }
+        // stored from a score
+        (_, Operation::Equal | Operation::FpEq, None) => {
+            let score = DataLocation::try_from(rhs.clone())
+                .map_err(|()| format!("Expected a number or score to assign to `::{name}`; got `{rhs:?}`"))?;
+            let objective = score.stringify_scoreboard_objective(config)?;
+            state.use_objective(&objective);
+            #[allow(clippy::cast_precision_loss)]
+            let scale = if op == Operation::FpEq {
+                1.0 / config.fixed_point_accuracy as f32
+            } else {
+                1.0
+            };
+            Ok(vec![Command::Execute {
+                options: store(selector, scaled_name, false, scale)?,
+                cmd: Box::new(Command::ScoreGet {
+                    target: score.stringify_scoreboard_target()?,
+                    objective,
+                }),
+            }]
+            .into())
+        }
+        _ => Err(format!("Can't operate `{target}::{name} {op} {rhs:?}`")),
+    }
+}
+
+/// get the execute options that store a value into a pseudo-property, like `@e[type=pig]::y := ...`
+///
+/// a property's own scale replaces `scale`
+pub(super) fn store(
+    selector: &Selector<Syntax>,
+    name: &str,
+    is_success: bool,
+    scale: f32,
+) -> SResult<Vec<ExecuteOption>> {
+    let Some(property) = Property::new(name) else {
+        return Err(format!("Unknown property `::{name}`"))
+    };
+    let target = selector.stringify()?;
+    if name.starts_with("food") {
+        return Err(String::from("`::food` can only be read; vanilla doesn't allow changing a player's food level"));
+    }
+    if is_player(&target) {
+        return Err(format!("Can't store a value in `{target}::{name}`; vanilla doesn't allow changing players' NBT"));
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let scale = property.scale.map_or(scale, |scale| 1.0 / scale as f32);
+    // storing into an entity only takes one entity, so store into each one as itself
+    let (mut options, target) = if is_single(&target) {
+        (Vec::new(), target)
+    } else {
+        (vec![ExecuteOption::As(target)], Selector::s())
+    };
+    options.push(ExecuteOption::StoreNBT {
+        location: NbtLocation::Entity(target, property.path),
+        is_success,
+        data_type: property.data_type.into(),
+        scale,
+    });
+    Ok(options)
+}
+
+/// whether a selector picks at most one entity, which is all `data` and `attribute` commands can take
+pub(super) fn is_single(target: &Selector<String>) -> bool {
+    target.args.get("limit").map_or(
+        matches!(target.selector_type, SelectorType::S | SelectorType::P | SelectorType::R),
+        |limit| limit == "1",
+    )
+}
+
+/// whether a selector only picks players, whose NBT can't be changed
+fn is_player(target: &Selector<String>) -> bool {
+    matches!(target.selector_type, SelectorType::A | SelectorType::P | SelectorType::R)
+        || target
+            .args
+            .get("type")
+            .is_some_and(|t| t == "player" || t == "minecraft:player")
+}
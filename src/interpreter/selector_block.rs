@@ -19,13 +19,25 @@ pub(super) fn block(
     match block_type {
         BlockType::Tp => teleport(selector, body),
         BlockType::Damage => damage(selector, body),
-        BlockType::Tellraw => tellraw(selector, body, config),
+        BlockType::Tellraw => Ok(vec![Command::TellRaw(
+            selector.stringify()?,
+            text_components(body, config)?,
+        )]
+        .into()),
+        BlockType::Title | BlockType::Subtitle | BlockType::Actionbar => {
+            Ok(vec![Command::Title {
+                target: selector.stringify()?,
+                kind: block_type.to_string().into(),
+                text: text_components(body, config)?,
+            }]
+            .into())
+        }
         block_type => selector_block(block_type, selector, body, state, path, src_files, config),
     }
 }
 
-/// interpret a tellraw block like `tellraw @a {...}`
-fn tellraw(selector: &Selector<Syntax>, properties: &Syntax, config: &Config) -> SResult<VecCmd> {
+/// get the JSON text of the body of a block like `tellraw @a [...]` or `title @a [...]`
+fn text_components(properties: &Syntax, config: &Config) -> SResult<RStr> {
     let mut nbt_buf: Vec<Nbt> = Vec::new();
 
     let arr = if let Syntax::Array(arr) = properties {
@@ -38,15 +50,11 @@ fn tellraw(selector: &Selector<Syntax>, properties: &Syntax, config: &Config) ->
         nbt_buf.push(tellraw_component(item, config)?);
     }
 
-    Ok(vec![Command::TellRaw(
-        selector.stringify()?,
-        Nbt::Array(nbt_buf).to_json().into(),
-    )]
-    .into())
+    Ok(Nbt::Array(nbt_buf).to_json().into())
 }
 
 /// get a tellraw component
-fn tellraw_component(src: &Syntax, config: &Config) -> SResult<Nbt> {
+pub(super) fn tellraw_component(src: &Syntax, config: &Config) -> SResult<Nbt> {
     match src {
         // a given object
         Syntax::Object(_) => Nbt::try_from(src),
@@ -161,7 +169,7 @@ fn damage(selector: &Selector<Syntax>, properties: &Syntax) -> SResult<VecCmd> {
         return Err(format!(
             "Damage annotation expected an object or integer; got `{properties:?}`"
         ));
-    };
+    }
     Ok(vec![Command::Damage {
         target: selector.stringify()?,
         amount,
@@ -201,6 +209,7 @@ fn selector_block(
         }),
         BlockType::Facing => res_buf.push(ExecuteOption::FacingEntity(selector)),
         BlockType::Rotated => res_buf.push(ExecuteOption::RotatedAs(selector)),
+        BlockType::PositionedAs => res_buf.push(ExecuteOption::PositionedAs(selector)),
         BlockType::As => {
             if selector == Selector::s() {
                 println!("\x1b[33mWARN\x1b[0m\t`as @s {{ ... }}`; This is a non-operation.");
@@ -219,6 +228,96 @@ fn selector_block(
     .into_vec())
 }
 
+/// interpret a loop over the entities of a selector, like `for entity in @e[type=zombie] {...}`
+///
+/// Each entity is tagged up front; the loop then runs the body as and at the next tagged entity
+/// (honoring the selector's `sort`) and untags it, so `break` untags the rest and flags the
+/// current iteration to skip the statements after it.
+pub(super) fn for_each(
+    index: DataLocation,
+    selector: &Selector<Syntax>,
+    body: &Syntax,
+    state: &mut InterRepr,
+    path: &Path,
+    src_files: &mut BTreeSet<PathBuf>,
+    config: &Config,
+) -> SResult<VecCmd> {
+    let hash = get_hash(&(selector, body, &index));
+    // for _ in @e => replace `_` with hash
+    let index = if index == DataLocation::Ident("_".into()) {
+        DataLocation::Ident(format!("{hash:x}").into())
+    } else {
+        index
+    };
+    let index_target = index.stringify_scoreboard_target()?;
+    let index_objective = index.stringify_scoreboard_objective(config)?;
+    state.use_objective(&index_objective);
+    let tag: RStr = format!("__foreach_{hash:x}").into();
+    let loop_name: RStr = format!("__internal__/foreach_{hash:x}").into();
+    let each_name: RStr = format!("__internal__/foreach_each_{hash:x}").into();
+
+    state.for_each_tags.push(tag.clone());
+    let outer_broke = core::mem::take(&mut state.broke_loop);
+    let inner = inner_interpret(body, state, path, src_files, config);
+    let can_break = core::mem::replace(&mut state.broke_loop, outer_broke);
+    state.for_each_tags.pop();
+    let mut each: VecCmd = vec![Command::Raw(format!("tag @s remove {tag}").into())].into();
+    each.extend(inner?);
+    state.functions.insert(each_name.clone(), each);
+
+    let selector = selector.stringify()?;
+    let remaining: Selector<String> = Selector::e().with_property("tag", tag.to_string());
+    // the next entity to visit
+    let mut next = Selector {
+        selector_type: selector.selector_type,
+        args: BTreeMap::new(),
+    }
+    .with_property("tag", tag.to_string())
+    .with_property("limit", String::from("1"));
+    if let Some(sort) = selector.args.get("sort") {
+        next = next.with_property("sort", sort.clone());
+    }
+    state.functions.insert(
+        loop_name.clone(),
+        vec![
+            Command::Execute {
+                options: vec![ExecuteOption::As(next), ExecuteOption::At(Selector::s())],
+                cmd: Box::new(Command::Function(each_name)),
+            },
+            Command::ScoreAdd {
+                target: index_target.clone(),
+                objective: index_objective.clone(),
+                value: 1,
+            },
+            Command::Execute {
+                options: vec![ExecuteOption::IfEntity {
+                    invert: false,
+                    selector: remaining.clone(),
+                }],
+                cmd: Box::new(Command::Function(loop_name.clone())),
+            },
+        ]
+        .into(),
+    );
+    let mut commands = vec![
+        // the first entity checks `i = 0`, so the index can't be reset
+        Command::score_init(&index_target, &index_objective, 0),
+        Command::Raw(format!("tag {selector} add {tag}").into()),
+        Command::Execute {
+            options: vec![ExecuteOption::IfEntity {
+                invert: false,
+                selector: remaining,
+            }],
+            cmd: Box::new(Command::Function(loop_name)),
+        },
+    ];
+    if can_break {
+        // set by `break`, so the rest of that iteration is skipped
+        commands.insert(1, Command::score_init(&format!("%{tag}"), &config.dummy_objective, 0));
+    }
+    Ok(commands.into())
+}
+
 /// interpret a teleport block
 /// `tp @s @p`
 /// `tp @s (~ ~ ~)`
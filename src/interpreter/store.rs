@@ -22,19 +22,40 @@ pub(super) fn storage_op(
         }]
     } else {
         match lhs {
+            DataLocation::SelectorDoubleColon(_, ident) if ident.starts_with("attr.") => {
+                return Err(String::from("Can't assign a command result to an attribute"))
+            }
+            DataLocation::SelectorDoubleColon(sel, ident) if super::property::is_property(&ident) => {
+                super::property::store(&sel, &ident, is_success, 1.0)?
+            }
             DataLocation::SelectorDoubleColon(_, _) => {
                 return Err(String::from("Can't assign a command result to an xp level"))
             }
             DataLocation::SelectorNbt(sel, nbt) => vec![ExecuteOption::StoreNBT {
                 location: NbtLocation::Entity(sel.stringify()?, nbt),
                 is_success,
+                data_type: "float".into(),
                 scale: 1.0,
             }],
             DataLocation::NbtStorage(nbt) => vec![ExecuteOption::StoreNBT {
                 location: NbtLocation::Storage(nbt),
                 is_success,
+                data_type: "float".into(),
                 scale: 1.0,
             }],
+            DataLocation::Bossbar(id, property) => {
+                if !matches!(&*property, "value" | "max") {
+                    return Err(format!("Can only store a command result in a bossbar's `value` or `max`, not `{property}`"));
+                }
+                if !state.bossbars.contains_key(&id) {
+                    return Err(format!("Unknown bossbar `{id}`; bossbars must be declared with `@bossbar` before they're used"));
+                }
+                vec![ExecuteOption::StoreBossbar {
+                    id,
+                    max: &*property == "max",
+                    is_success,
+                }]
+            }
             _ => unreachable!(),
         }
     };
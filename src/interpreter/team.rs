@@ -0,0 +1,151 @@
+use super::{selector_block::tellraw_component, InterRepr};
+use crate::{types::prelude::*, Config};
+
+/// declare a team like `@team red { color: red, friendly_fire: false, prefix: "[R] " }`
+pub(super) fn declaration(
+    properties: &Syntax,
+    state: &mut InterRepr,
+    config: &Config,
+) -> SResult<()> {
+    let Syntax::BinaryOp { lhs, operation: Operation::Colon, rhs } = properties else {
+        return Err(format!("Expected `@team {{name}} {{...}}`; got `@team {properties:?}`"))
+    };
+    let Syntax::Identifier(name) = &**lhs else {
+        return Err(format!("Team name must be an identifier; got `{lhs:?}`"))
+    };
+    if state.teams.contains_key(name) {
+        return Err(format!("Team `{name}` is already declared"));
+    }
+    let mut display_name = Nbt::String(name.clone()).to_json();
+    let mut options = Vec::new();
+    match &**rhs {
+        Syntax::Object(obj) => {
+            for (k, v) in obj {
+                if matches!(&**k, "name" | "display_name") {
+                    display_name = tellraw_component(v, config)?.to_json();
+                } else {
+                    options.push(modify(name, k, v, config)?);
+                }
+            }
+        }
+        Syntax::Unit => {}
+        other => return Err(format!("Expected an object for team `{name}`; got `{other:?}`")),
+    }
+    let mut commands = vec![Command::TeamAdd {
+        name: name.clone(),
+        display_name: display_name.into(),
+    }];
+    commands.extend(options);
+    state.teams.insert(name.clone(), commands);
+    Ok(())
+}
+
+/// get the command that sets a team option, like `collision: never`
+fn modify(name: &RStr, option: &str, value: &Syntax, config: &Config) -> SResult<Command> {
+    let invalid = || format!("Invalid value for team `{name}`'s `{option}`: `{value:?}`");
+    let (option, value): (&str, RStr) = match option {
+        "color" => match value {
+            Syntax::Identifier(color) if COLORS.contains(&&**color) => ("color", color.clone()),
+            _ => return Err(invalid()),
+        },
+        "friendly_fire" | "see_friendly_invisibles" => match value {
+            Syntax::Identifier(bool) if matches!(&**bool, "true" | "false") => (
+                if option == "friendly_fire" {
+                    "friendlyFire"
+                } else {
+                    "seeFriendlyInvisibles"
+                },
+                bool.clone(),
+            ),
+            _ => return Err(invalid()),
+        },
+        "collision" => match value {
+            Syntax::Identifier(rule) => (
+                "collisionRule",
+                match &**rule {
+                    "always" | "never" => rule.clone(),
+                    "push_other_teams" | "pushOtherTeams" => "pushOtherTeams".into(),
+                    "push_own_team" | "pushOwnTeam" => "pushOwnTeam".into(),
+                    _ => return Err(invalid()),
+                },
+            ),
+            _ => return Err(invalid()),
+        },
+        "nametag_visibility" | "death_message_visibility" => match value {
+            Syntax::Identifier(rule) => (
+                if option == "nametag_visibility" {
+                    "nametagVisibility"
+                } else {
+                    "deathMessageVisibility"
+                },
+                match &**rule {
+                    "always" | "never" => rule.clone(),
+                    "hide_for_other_teams" | "hideForOtherTeams" => "hideForOtherTeams".into(),
+                    "hide_for_own_team" | "hideForOwnTeam" => "hideForOwnTeam".into(),
+                    _ => return Err(invalid()),
+                },
+            ),
+            _ => return Err(invalid()),
+        },
+        "prefix" | "suffix" => (option, tellraw_component(value, config)?.to_json().into()),
+        other => return Err(format!("Invalid team option: `{other}`")),
+    };
+    Ok(Command::TeamModify {
+        name: name.clone(),
+        option: option.into(),
+        value,
+    })
+}
+
+/// interpret `team red += @a[tag=red]` or `team red -= @a`
+pub(super) fn membership(properties: &Syntax, state: &InterRepr) -> SResult<VecCmd> {
+    let Syntax::BinaryOp { lhs, operation: operation @ (Operation::AddEq | Operation::SubEq), rhs } = properties else {
+        return Err(format!("`team` takes `{{team}} += {{selector}}` or `{{team}} -= {{selector}}`; got `{properties:?}`"))
+    };
+    let Syntax::Identifier(name) = &**lhs else {
+        return Err(format!("Team name must be an identifier; got `{lhs:?}`"))
+    };
+    if !state.teams.contains_key(name) {
+        return Err(format!("Unknown team `{name}`; teams must be declared with `@team` before they're used"));
+    }
+    let Syntax::Selector(members) = &**rhs else {
+        return Err(format!("Only selectors can join or leave a team; got `{rhs:?}`"))
+    };
+    let members = members.stringify()?;
+    if *operation == Operation::AddEq {
+        return Ok(vec![Command::TeamJoin {
+            name: name.clone(),
+            members,
+        }]
+        .into());
+    }
+    // `team leave` takes entities off whatever team they're on, so only pick the ones on this team
+    if members.args.contains_key("team") {
+        return Err(format!("`{members}` already checks a team; can't leave team `{name}`"));
+    }
+    Ok(vec![Command::TeamLeave {
+        members: members.with_property("team", name.to_string()),
+    }]
+    .into())
+}
+
+/// the colors a team can have
+const COLORS: [&str; 17] = [
+    "black",
+    "dark_blue",
+    "dark_green",
+    "dark_aqua",
+    "dark_red",
+    "dark_purple",
+    "gold",
+    "gray",
+    "dark_gray",
+    "blue",
+    "green",
+    "aqua",
+    "red",
+    "light_purple",
+    "yellow",
+    "white",
+    "reset",
+];
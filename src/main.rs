@@ -29,7 +29,10 @@ mod types;
 #[cfg(test)]
 mod tests;
 
-pub const MAX_VERSION: u8 = 16;
+/// the newest pack format the datapack is written for
+pub const MAX_VERSION: u8 = 57;
+/// the pack format that renamed the data folders to singular, like `functions/` to `function/`
+pub const RENAMED_VERSION: u8 = 48;
 
 macro_rules! input {
     ($msg: expr) => {{
@@ -61,12 +64,16 @@ struct Args {
     /// Accuracy of fixed-point calculations. Higher numbers are more precise
     #[clap(short, long, default_value = "1000")]
     fixed_point_accuracy: i32,
+    /// Make it an error to use a scoreboard objective that isn't declared with `@objective`
+    #[clap(long)]
+    strict_objectives: bool,
 }
 
 pub struct Config {
     namespace: String,
     dummy_objective: RStr,
     fixed_point_accuracy: i32,
+    strict_objectives: bool,
 }
 
 fn main() -> SResult<()> {
@@ -78,19 +85,17 @@ fn main() -> SResult<()> {
             .dummy
             .map_or_else(|| RStr::from("dummy"), |dummy| fmt_mc_ident(&dummy).into()),
         fixed_point_accuracy: args.fixed_point_accuracy,
+        strict_objectives: args.strict_objectives,
     };
     // load environment variables from `.env` file
     dotenv().ok();
     // either get "DOTMINECRAFT" from env or ask for it
-    let dotminecraft = env::var("DOTMINECRAFT").map_or_else(
-        |e| {
-            println!("{e}");
-            let dm = input!("Provide the location of your `.minecraft` folder:");
-            env::set_var("DOTMINECRAFT", &dm);
-            dm
-        },
-        |val| val,
-    );
+    let dotminecraft = env::var("DOTMINECRAFT").unwrap_or_else(|e| {
+        println!("{e}");
+        let dm = input!("Provide the location of your `.minecraft` folder:");
+        env::set_var("DOTMINECRAFT", &dm);
+        dm
+    });
     // set the parent folder for the compiled output
     let parent = args.world.map_or_else(
         || format!("{dotminecraft}/datapacks/"),
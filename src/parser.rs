@@ -36,7 +36,7 @@ fn inner_parse_expr_greedy(
                     rhs: Box::new(rhs),
                 };
             }
-            Some(Token::Identifier(id)) if priority == 2 && &**id == "in" => {
+            Some(Token::Identifier(id)) if priority == 2 && matches!(&**id, "in" | "has") => {
                 let op = tokens.next().unwrap().try_into().unwrap();
                 let rhs = inner_parse_expr_greedy(tokens, priority + 1)?;
                 start = Syntax::BinaryOp {
@@ -80,6 +80,33 @@ fn inner_parse_expr_greedy(
     }
 }
 
+/// get the rest of a `::` property, like `attr.generic.max_health` or `x(1000)`
+fn parse_double_colon<T: Iterator<Item = Token>>(ident: RStr, tokens: &mut Peekable<T>) -> SResult<RStr> {
+    match tokens.peek() {
+        // `@s::attr.generic.max_health`
+        Some(Token::Dot) => {
+            let mut path = String::from(&*ident);
+            for part in parse_nbt_path(tokens)? {
+                let NbtPathPart::Ident(part) = part else {
+                    return Err(format!("Expected a name after `@s::{path}.`; got an index"))
+                };
+                path.push('.');
+                path.push_str(&part);
+            }
+            Ok(path.into())
+        }
+        // `@s::x(1000)` gives the property its own scale
+        Some(Token::LParen) => {
+            tokens.next();
+            let (Some(Token::Integer(scale)), Some(Token::RParen)) = (tokens.next(), tokens.next()) else {
+                return Err(format!("Expected a scale like `@s::{ident}(100)`"))
+            };
+            Ok(format!("{ident}({scale})").into())
+        }
+        _ => Ok(ident),
+    }
+}
+
 #[allow(clippy::cast_precision_loss, clippy::cast_possible_wrap)]
 fn inner_parse<T: Iterator<Item = Token>>(tokens: &mut Peekable<T>) -> SResult<Syntax> {
     let first = match tokens.next() {
@@ -132,7 +159,7 @@ fn inner_parse<T: Iterator<Item = Token>>(tokens: &mut Peekable<T>) -> SResult<S
             };
             let left = match tok {
                 Some(Token::Colon) => Syntax::SelectorColon(sel.clone(), ident),
-                Some(Token::DoubleColon) => Syntax::SelectorDoubleColon(sel.clone(), ident),
+                Some(Token::DoubleColon) => Syntax::SelectorDoubleColon(sel.clone(), parse_double_colon(ident, tokens)?),
                 Some(Token::Dot) => {
                     let mut nbt = vec![NbtPathPart::Ident(ident)];
                     nbt.extend(parse_nbt_path(tokens)?);
@@ -320,9 +347,66 @@ fn parse_annotation<T: Iterator<Item = Token>>(tokens: &mut Peekable<T>) -> SRes
                 args: selector_buf,
             }))
         }
+        // annotations that declare something with a name, like `@enum State { ... }`
+        "enum" | "objective" | "bossbar" | "team" => {
+            let Some(Token::Identifier(name)) = tokens.next() else {
+                return Err(format!("Expected a name after `@{identifier}`"))
+            };
+            Ok(Syntax::Annotation(
+                identifier,
+                Box::new(Syntax::BinaryOp {
+                    lhs: Box::new(Syntax::Identifier(name)),
+                    operation: Operation::Colon,
+                    rhs: Box::new(inner_parse(tokens)?),
+                }),
+            ))
+        }
+        "schedule" => Ok(Syntax::Annotation(identifier, Box::new(parse_schedule(tokens)?))),
         _ => Ok(Syntax::Annotation(
             identifier,
             Box::new(inner_parse_expr_greedy(tokens, 0)?),
         )),
     }
 }
+
+/// parse `@schedule my_func in 5s replace` or `@schedule 2s {...}` into `[function or body, duration, mode]`
+fn parse_schedule<T: Iterator<Item = Token>>(tokens: &mut Peekable<T>) -> SResult<Syntax> {
+    let first = tokens.next();
+    let (func, duration) = if tokens.peek() == Some(&Token::Identifier("in".into())) {
+        tokens.next();
+        let func = match first {
+            Some(Token::Identifier(func) | Token::String(func)) => Syntax::String(func),
+            other => return Err(format!("Expected a function to schedule; got `{other:?}`")),
+        };
+        (Some(func), tokens.next())
+    } else {
+        (None, first)
+    };
+    let duration = match duration {
+        // `1.5s` is a float followed by a unit
+        Some(Token::Float(float)) => match tokens.peek() {
+            Some(Token::Identifier(unit)) if matches!(&**unit, "t" | "s" | "d") => {
+                let unit = unit.clone();
+                tokens.next();
+                Syntax::Identifier(format!("{float}{unit}").into())
+            }
+            _ => Syntax::Float(float),
+        },
+        Some(Token::Identifier(duration)) => Syntax::Identifier(duration),
+        Some(Token::Integer(ticks)) => Syntax::Integer(ticks),
+        other => return Err(format!("Expected a time like `5s`, `20t`, or `1d` to schedule for; got `{other:?}`")),
+    };
+    let mode = match tokens.peek() {
+        Some(Token::Identifier(mode)) if matches!(&**mode, "replace" | "append") => {
+            let mode = mode.clone();
+            tokens.next();
+            Syntax::Identifier(mode)
+        }
+        _ => Syntax::Unit,
+    };
+    let func = match func {
+        Some(func) => func,
+        None => inner_parse(tokens)?,
+    };
+    Ok(Syntax::Array([func, duration, mode].into()))
+}
@@ -23,10 +23,46 @@ pub(super) fn parse_identifier<T: Iterator<Item = Token>>(
             operation: Operation::SubEq,
             rhs: Box::new(Syntax::Integer(1)),
         })
+    } else if tokens.peek() == Some(&Token::DoubleColon) {
+        // enum variant like `State::Idle` or a property like `bossbar::fight.value`
+        tokens.next();
+        let Some(Token::Identifier(variant)) = tokens.next() else {
+            return Err(format!("Expected an identifier after `{id}::`"))
+        };
+        let rhs = if tokens.peek() == Some(&Token::Dot) {
+            let mut path = vec![NbtPathPart::Ident(variant)];
+            path.extend(parse_nbt_path(tokens)?);
+            Syntax::NbtStorage(path)
+        } else {
+            Syntax::Identifier(variant)
+        };
+        Ok(Syntax::BinaryOp {
+            lhs: Box::new(Syntax::Identifier(id)),
+            operation: Operation::DoubleColon,
+            rhs: Box::new(rhs),
+        })
     } else if tokens.peek() == Some(&Token::Dot) {
         let mut path = vec![NbtPathPart::Ident(id)];
         path.extend(parse_nbt_path(tokens)?);
         Ok(Syntax::NbtStorage(path))
+    } else if &*id == "tag" && tokens.peek() == Some(&Token::At) {
+        // `tag @s += marked` is shorthand for `@tag @s += marked`
+        Ok(Syntax::Annotation(
+            id,
+            Box::new(inner_parse_expr_greedy(tokens, 0)?),
+        ))
+    } else if &*id == "team" && matches!(tokens.peek(), Some(Token::Identifier(_))) {
+        // `team red += @a` is shorthand for `@team red += @a`
+        Ok(Syntax::Annotation(
+            id,
+            Box::new(inner_parse_expr_greedy(tokens, 0)?),
+        ))
+    } else if matches!(&*id, "title" | "subtitle" | "actionbar") && tokens.peek() != Some(&Token::At) {
+        // only a block when it's followed by a selector, so `title` still works as a name
+        Ok(Syntax::Identifier(id))
+    } else if tokens.peek() == Some(&Token::Colon) {
+        // a key like `facing: north`, even if it's also a block type
+        Ok(Syntax::Identifier(id))
     } else if let Ok(mut block_type) = BlockType::try_from(&*id) {
         if block_type == BlockType::As && tokens.peek() == Some(&Token::Identifier("at".into())) {
             tokens.next();
@@ -41,17 +77,226 @@ pub(super) fn parse_identifier<T: Iterator<Item = Token>>(
         {
             tokens.next();
             block_type = BlockType::DoUntil;
+        } else if block_type == BlockType::Positioned
+            && tokens.peek() == Some(&Token::Identifier("over".into()))
+        {
+            tokens.next();
+            block_type = BlockType::PositionedOver;
+        } else if block_type == BlockType::Positioned
+            && tokens.peek() == Some(&Token::Identifier("as".into()))
+        {
+            tokens.next();
+            block_type = BlockType::PositionedAs;
         } else if block_type == BlockType::Do {
             return Err(String::from(
                 "`do` is not a valid block type; did you mean `do while` or `do until`?",
             ));
         }
+        let header = match block_type {
+            BlockType::For => parse_for_header(tokens)?,
+            // `if block (~ ~-1 ~) grass_block {...}`
+            BlockType::If | BlockType::Unless
+                if matches!(tokens.peek(), Some(Token::Identifier(kind)) if CONDITIONS.contains(&&**kind)) =>
+            {
+                let Some(Token::Identifier(kind)) = tokens.next() else {
+                    unreachable!()
+                };
+                parse_condition(tokens, kind)?
+            }
+            // `summon zombie (~ ~1 ~) { nbt: {...}, tags: [...] } {...}`
+            BlockType::Summon => {
+                let entity = inner_parse(tokens)?;
+                let pos = if tokens.peek() == Some(&Token::LParen) {
+                    inner_parse(tokens)?
+                } else {
+                    Syntax::Unit
+                };
+                if pos == Syntax::Unit && tokens.peek() != Some(&Token::LCurly) {
+                    // `summon sheep @raw "..."`
+                    return Ok(Syntax::Block(
+                        block_type,
+                        Box::new(entity),
+                        Box::new(inner_parse(tokens)?),
+                    ));
+                }
+                if tokens.peek() != Some(&Token::LCurly) {
+                    // `summon zombie (~ ~1 ~)` with no body
+                    return Ok(Syntax::Block(
+                        block_type,
+                        Box::new(Syntax::Array([entity, pos, Syntax::Unit].into())),
+                        Box::new(Syntax::Unit),
+                    ));
+                }
+                let first = inner_parse(tokens)?;
+                // a second object means the first one was the entity's properties
+                if tokens.peek() == Some(&Token::LCurly) {
+                    return Ok(Syntax::Block(
+                        block_type,
+                        Box::new(Syntax::Array([entity, pos, first].into())),
+                        Box::new(inner_parse(tokens)?),
+                    ));
+                }
+                let header = if pos == Syntax::Unit {
+                    entity
+                } else {
+                    Syntax::Array([entity, pos, Syntax::Unit].into())
+                };
+                return Ok(Syntax::Block(block_type, Box::new(header), Box::new(first)));
+            }
+            // `default` arm of a switch has no header
+            BlockType::Default => Syntax::Unit,
+            // `case 1, 2, 5 {...}`
+            BlockType::Case => {
+                let mut values = vec![inner_parse_expr_greedy(tokens, 0)?];
+                while tokens.peek() == Some(&Token::Comma) {
+                    tokens.next();
+                    values.push(inner_parse_expr_greedy(tokens, 0)?);
+                }
+                if values.len() == 1 {
+                    values.pop().unwrap()
+                } else {
+                    Syntax::Array(values.into())
+                }
+            }
+            _ => inner_parse_expr_greedy(tokens, 0)?,
+        };
         Ok(Syntax::Block(
             block_type,
-            Box::new(inner_parse_expr_greedy(tokens, 0)?),
+            Box::new(header),
             Box::new(inner_parse(tokens)?),
         ))
     } else {
         Ok(Syntax::Identifier(id))
     }
 }
+
+/// vanilla `execute if` subcommands that can be used as the header of an `if` or `unless` block
+const CONDITIONS: [&str; 8] = [
+    "block",
+    "blocks",
+    "data",
+    "predicate",
+    "biome",
+    "dimension",
+    "loaded",
+    "function",
+];
+
+/// parse a vanilla `execute if` condition, like `block (~ ~-1 ~) grass_block` or `data @s.SelectedItem`
+fn parse_condition<T: Iterator<Item = Token>>(
+    tokens: &mut Peekable<T>,
+    kind: RStr,
+) -> SResult<Syntax> {
+    // a variable that happens to share a name with a condition, like `if data = 1`
+    if let Some(Ok(operation)) = tokens.peek().cloned().map(Operation::try_from) {
+        tokens.next();
+        return Ok(Syntax::BinaryOp {
+            lhs: Box::new(Syntax::Identifier(kind)),
+            operation,
+            rhs: Box::new(inner_parse_expr_greedy(tokens, 4)?),
+        });
+    }
+    let arity = match &*kind {
+        "block" | "biome" => 2,
+        "blocks" => 3,
+        _ => 1,
+    };
+    let mut args = Vec::new();
+    for _ in 0..arity {
+        args.push(inner_parse(tokens)?);
+    }
+    match &*kind {
+        // `blocks (...) (...) (...) masked`
+        "blocks"
+            if matches!(tokens.peek(), Some(Token::Identifier(mode)) if matches!(&**mode, "all" | "masked")) =>
+        {
+            args.push(inner_parse(tokens)?);
+        }
+        // block entity data, like `data (~ ~ ~) "Items[0]"`
+        "data" if args[0].is_array() => args.push(inner_parse(tokens)?),
+        _ => {}
+    }
+    Ok(Syntax::Condition(kind, args.into()))
+}
+
+/// parse the header of a `for` loop, like `i in 0..10`, `i in n..<0 step -2`, or `entity in @e`
+fn parse_for_header<T: Iterator<Item = Token>>(tokens: &mut Peekable<T>) -> SResult<Syntax> {
+    let (var, start) = match inner_parse(tokens)? {
+        // `@s:i in ..` is picked up as an operation by the selector parser
+        Syntax::BinaryOp {
+            lhs,
+            operation: Operation::In,
+            rhs,
+        } => (*lhs, *rhs),
+        var => {
+            let Some(Token::Identifier(ident)) = tokens.next() else {
+                return Err(format!("Expected `in` after `for {var:?}`"))
+            };
+            if &*ident != "in" {
+                return Err(format!("Expected `in` after `for {var:?}`; got `{ident}`"));
+            }
+            (var, inner_parse(tokens)?)
+        }
+    };
+    let (start, end) = match start {
+        // for entity in @e[...]
+        Syntax::Selector(_) => return Ok(for_in(var, start)),
+        Syntax::Range(start, Some(end)) => (
+            Syntax::Integer(start.unwrap_or(0)),
+            Some(Syntax::Integer(end)),
+        ),
+        Syntax::Range(start, None) => (Syntax::Integer(start.unwrap_or(0)), None),
+        start => {
+            if tokens.next() != Some(Token::DotDot) {
+                return Err(format!("Expected `..` after `for {var:?} in {start:?}`"));
+            }
+            (start, None)
+        }
+    };
+    let (inclusive, explicit) = match tokens.peek() {
+        Some(Token::LCaret) if end.is_none() => {
+            tokens.next();
+            (false, true)
+        }
+        Some(Token::Equal) if end.is_none() => {
+            tokens.next();
+            (true, true)
+        }
+        _ => (true, false),
+    };
+    let end = match end {
+        Some(end) => end,
+        None => inner_parse(tokens)?,
+    };
+    let step = if tokens.peek() == Some(&Token::Identifier("step".into())) {
+        tokens.next();
+        match inner_parse(tokens)? {
+            Syntax::Integer(step) => Some(step),
+            other => return Err(format!("Expected an integer `for` loop step; got `{other:?}`")),
+        }
+    } else {
+        None
+    };
+    // keep simple literal ranges as they are
+    if let (Syntax::Integer(start), Syntax::Integer(end), false, None) = (&start, &end, explicit, step)
+    {
+        return Ok(for_in(var, Syntax::Range(Some(*start), Some(*end))));
+    }
+    Ok(for_in(
+        var,
+        Syntax::ForRange {
+            start: Box::new(start),
+            end: Box::new(end),
+            inclusive,
+            step,
+        },
+    ))
+}
+
+fn for_in(var: Syntax, range: Syntax) -> Syntax {
+    Syntax::BinaryOp {
+        lhs: Box::new(var),
+        operation: Operation::In,
+        rhs: Box::new(range),
+    }
+}
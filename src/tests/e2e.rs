@@ -17,21 +17,51 @@ macro_rules! assert_e2e {
     }};
 }
 
-macro_rules! build_e2e {
-    ($src: expr) => {{
+/// interpret a program without compiling it, optionally with `strict_objectives` set
+macro_rules! interpret_e2e {
+    ($src: expr) => {
+        interpret_e2e!($src, strict_objectives: false)
+    };
+    ($src: expr, strict_objectives: $strict: expr) => {{
         let tokens = $crate::lexer::tokenize(&format!("[{}]", $src)).unwrap();
         let syntax = $crate::parser::parse(tokens).unwrap();
-        let mut inter = $crate::interpreter::interpret(
+        $crate::interpreter::interpret(
             &syntax,
             ::std::path::Path::new(""),
             &mut ::std::collections::BTreeSet::new(),
-            &$crate::Config { namespace: "test".into(), dummy_objective: "dummy".into(), fixed_point_accuracy: 100 }
+            &$crate::Config { namespace: "test".into(), dummy_objective: "dummy".into(), fixed_point_accuracy: 100, strict_objectives: $strict }
         )
-        .unwrap();
+        .unwrap()
+    }};
+}
+
+macro_rules! build_e2e {
+    ($src: expr) => {{
+        let mut inter = interpret_e2e!($src);
         $crate::compiler::compile(&mut inter, "test").unwrap()
     }};
 }
 
+/// the error from parsing or interpreting a program that should fail
+macro_rules! e2e_error {
+    ($src: expr) => {
+        e2e_error!($src, strict_objectives: false)
+    };
+    ($src: expr, strict_objectives: $strict: expr) => {{
+        $crate::lexer::tokenize(&format!("[{}]", $src))
+            .and_then($crate::parser::parse)
+            .and_then(|syntax| {
+                $crate::interpreter::interpret(
+                    &syntax,
+                    ::std::path::Path::new(""),
+                    &mut ::std::collections::BTreeSet::new(),
+                    &$crate::Config { namespace: "test".into(), dummy_objective: "dummy".into(), fixed_point_accuracy: 100, strict_objectives: $strict }
+                )
+            })
+            .unwrap_err()
+    }};
+}
+
 #[test]
 fn control_flow() {
     assert_e2e!("if x = 1 { @function \"use/goodberry\" }" =>
@@ -41,6 +71,7 @@ fn control_flow() {
     assert_e2e!("if @s:score != 2 @raw \"...\"" => "execute unless score @s score matches 2 run ...");
     assert_e2e!("unless x >= 1 @raw \"...\"" => "execute unless score %x dummy matches 1.. run ...");
     assert_e2e!("unless x > 1 @raw \"...\"" => "execute if score %x dummy matches ..1 run ...");
+    assert_e2e!("if x < y @raw \"...\"" => "execute if score %x dummy < %y dummy run ...");
     assert_e2e!("if @s[type=cow] @raw \"...\"" =>"execute if entity @s[type=cow] run ...");
     assert_e2e!("unless @s[type=cow] @raw \"...\"" => "execute unless entity @s[type=cow] run ...");
     assert_e2e!("if @s::lvl > 10 @raw \"...\""
@@ -106,6 +137,99 @@ fn loops() {
     &format!("\nscoreboard players add %x dummy 1\nexecute unless score %x dummy matches 10 run function test:{do_until_inner}"));
 }
 
+#[test]
+fn for_ranges() {
+    let count_up = build_e2e!("function load for i in 0..<n step 2 @raw \"...\"");
+    let count_up_inner: RStr = lazy_regex!(".*\nexecute if score %i dummy < %(__for_end__[0-9a-f]+) dummy run function test:(__internal__/[0-9a-f]+)")
+        .captures(count_up.functions.get("load").unwrap().base())
+        .unwrap()
+        .get(2)
+        .unwrap()
+        .as_str()
+        .into();
+    assert_eq!(count_up.functions.get("load").unwrap().base(), &format!(
+        "\nscoreboard players set %i dummy 0\nscoreboard players operation %__for_end__{0} dummy = %n dummy\nexecute if score %i dummy < %__for_end__{0} dummy run function test:{count_up_inner}",
+        &count_up_inner["__internal__/".len()..]
+    ));
+    assert_eq!(count_up.functions.get(&count_up_inner).unwrap().base(), &format!(
+        "\n...\nscoreboard players add %i dummy 2\nexecute if score %i dummy < %__for_end__{} dummy run function test:{count_up_inner}",
+        &count_up_inner["__internal__/".len()..]
+    ));
+
+    let count_down = build_e2e!("function load for i in 10..0 @raw \"...\"");
+    let count_down_inner: RStr = lazy_regex!(".*\nfunction test:(__internal__/[0-9a-f]+)")
+        .captures(count_down.functions.get("load").unwrap().base())
+        .unwrap()
+        .get(1)
+        .unwrap()
+        .as_str()
+        .into();
+    assert_eq!(count_down.functions.get(&count_down_inner).unwrap().base(),
+    &format!("\n...\nscoreboard players remove %i dummy 1\nexecute if score %i dummy matches 0..10 run function test:{count_down_inner}"));
+
+    let exclusive = build_e2e!("function load for i in 10..<0 step -5 @raw \"...\"");
+    let exclusive_inner: RStr = lazy_regex!(".*\nfunction test:(__internal__/[0-9a-f]+)")
+        .captures(exclusive.functions.get("load").unwrap().base())
+        .unwrap()
+        .get(1)
+        .unwrap()
+        .as_str()
+        .into();
+    assert_eq!(exclusive.functions.get(&exclusive_inner).unwrap().base(),
+    &format!("\n...\nscoreboard players remove %i dummy 5\nexecute if score %i dummy matches 1..10 run function test:{exclusive_inner}"));
+
+    // a range starting at a score could go either way
+    assert!(e2e_error!("function load for i in n..0 @raw \"...\"").contains("needs a `step`"));
+    let count_down_dynamic = build_e2e!("function load for i in n..0 step -1 @raw \"...\"");
+    assert!(count_down_dynamic.functions.get("load").unwrap().base().starts_with("\nscoreboard players operation %i dummy = %n dummy\nexecute if score %i dummy matches 0.. run function test:"));
+}
+
+#[test]
+fn for_each() {
+    let for_each = build_e2e!("function zombies for i in @e[type=zombie, sort=nearest] {
+    @raw \"say hi\"
+    if i = 3 break
+    @raw \"say bye\"
+}");
+    let for_hash: RStr = lazy_regex!("tag @e\\[sort=nearest, type=zombie\\] add __foreach_([0-9a-f]+)")
+        .captures(for_each.functions.get("zombies").unwrap().base())
+        .unwrap()
+        .get(1)
+        .unwrap()
+        .as_str()
+        .into();
+    assert_eq!(for_each.functions.get("zombies").unwrap().base(), &format!("
+scoreboard players set %i dummy 0
+scoreboard players set %__foreach_{for_hash} dummy 0
+tag @e[sort=nearest, type=zombie] add __foreach_{for_hash}
+execute if entity @e[tag=__foreach_{for_hash}] run function test:__internal__/foreach_{for_hash}"));
+    assert_eq!(for_each.functions.get::<str>(&format!("__internal__/foreach_{for_hash}")).unwrap().base(), &format!("
+execute as @e[limit=1, sort=nearest, tag=__foreach_{for_hash}] at @s run function test:__internal__/foreach_each_{for_hash}
+scoreboard players add %i dummy 1
+execute if entity @e[tag=__foreach_{for_hash}] run function test:__internal__/foreach_{for_hash}"));
+    let each = for_each.functions.get::<str>(&format!("__internal__/foreach_each_{for_hash}")).unwrap().base();
+    let break_fn = lazy_regex!("matches 3 run function test:(__internal__/if_[0-9a-f]+)").captures(each).unwrap().get(1).unwrap().as_str();
+    assert_eq!(each, &format!("
+tag @s remove __foreach_{for_hash}
+say hi
+execute if score %i dummy matches 3 run function test:{break_fn}
+execute unless score %__foreach_{for_hash} dummy matches 1 run say bye"));
+    // `break` stops the loop and skips the rest of the current iteration
+    assert_eq!(for_each.functions.get(break_fn).unwrap().base(), &format!("
+tag @e[tag=__foreach_{for_hash}] remove __foreach_{for_hash}
+scoreboard players set %__foreach_{for_hash} dummy 1"));
+
+    let same_body = build_e2e!("function zombies [
+    for i in @e[type=zombie] @raw \"say hi\"
+    for j in @e[type=zombie] @raw \"say hi\"
+]");
+    let tags: std::collections::BTreeSet<&str> = lazy_regex!("add (__foreach_[0-9a-f]+)")
+        .captures_iter(same_body.functions.get("zombies").unwrap().base())
+        .map(|captures| captures.get(1).unwrap().as_str())
+        .collect();
+    assert_eq!(tags.len(), 2);
+}
+
 #[test]
 fn execution_context() {
     assert_e2e!("as at @a @raw \"...\"" => "execute as @a at @s run ...");
@@ -219,9 +343,584 @@ fn advancement() {
     }
     ");
 
-    let advancement = advancement_repr.advancements.get("join").unwrap().clone();
+    let advancement = advancement_repr.advancements.get("join").unwrap().base().clone();
     println!("{advancement:?}");
     let advancement_hash = lazy_regex!(r#"^\{"criteria":\s*\{"requirement":\s*\{"trigger":\s*"minecraft:tick"\}\},\s*"rewards":\{"function":"([a-z0-9:_/]+)"\}\}$"#).captures(&advancement).unwrap().get(1).unwrap().as_str().to_string();
 
     assert_eq!(advancement_repr.functions.get(&*advancement_hash).unwrap().base().trim(), "...");
 }
+
+#[test]
+fn enums() {
+    assert_e2e!("@enum State { Idle, Charging, Firing = 5, Cooling } state = State::Cooling" =>
+        "scoreboard players set %state dummy 6"
+    );
+    assert_e2e!("@enum State { Idle, Firing } if state = State::Firing @raw \"...\"" =>
+        "execute if score %state dummy matches 1 run ..."
+    );
+    let compiled = build_e2e!("@enum State { Idle, Firing } function load switch state { case State::Idle @raw \"idle\" case State::Firing @raw \"firing\" }");
+    let main = compiled.functions.get("load").unwrap().base().clone();
+    assert!(main.contains("dummy matches 0 run idle"), "{main}");
+    assert!(main.contains("dummy matches 1 run firing"), "{main}");
+    assert!(e2e_error!("@enum State { Idle, Firing, Idle }").contains("more than one variant named `Idle`"));
+    assert!(e2e_error!("@enum State { Idle = 1, Firing = 1 }").contains("gives `Firing` the same value as `Idle`"));
+    assert!(e2e_error!("@enum State { Idle, Firing = 0 }").contains("gives `Firing` the same value as `Idle`"));
+    assert!(e2e_error!("@enum State { Idle: 0, Idle: 1 }").contains("gives its variants values with `=`"));
+}
+
+#[test]
+fn switch() {
+    let small = build_e2e!("function load switch x { case 1, 2 @raw \"a\" case 3..7 @raw \"b\" default @raw \"c\" }");
+    let small = lazy_regex!("switch_[0-9a-f]+").replace_all(small.functions.get("load").unwrap().base(), "switch").to_string();
+    assert_eq!(small.trim(), "scoreboard players operation %__internal__/switch dummy = %x dummy
+execute if score %__internal__/switch dummy matches 1 run a
+execute if score %__internal__/switch dummy matches 2 run a
+execute if score %__internal__/switch dummy matches 3..7 run b
+execute unless score %__internal__/switch dummy matches 1 unless score %__internal__/switch dummy matches 2 unless score %__internal__/switch dummy matches 3..7 run c");
+
+    let large = build_e2e!("function load switch x { case 0 @raw \"0\" case 1 @raw \"1\" case 2 @raw \"2\" case 3 @raw \"3\" case 4 @raw \"4\" case 5 @raw \"5\" default @raw \"d\" }");
+    let load = large.functions.get("load").unwrap().base();
+    let lower: RStr = lazy_regex!("matches \\.\\.2 run function test:(__internal__/switch_[0-9a-f_]+)")
+        .captures(load)
+        .unwrap()
+        .get(1)
+        .unwrap()
+        .as_str()
+        .into();
+    assert!(load.contains("matches 3.. run function test:__internal__/switch_"), "{load}");
+    let lower = large.functions.get(&lower).unwrap().base();
+    assert!(lower.contains("matches 0 run 0"), "{lower}");
+    assert!(lower.contains("unless score %__internal__/"), "{lower}");
+    assert!(!lower.contains("run 3"), "{lower}");
+}
+
+#[test]
+fn execute_conditions() {
+    assert_e2e!("if block (~ ~-1 ~) grass_block @raw \"...\"" => "execute if block ~ ~-1 ~ grass_block run ...");
+    assert_e2e!("unless block (~ ~-1 ~) \"#minecraft:logs\" @raw \"...\"" => "execute unless block ~ ~-1 ~ #minecraft:logs run ...");
+    assert_e2e!("if blocks (0 0 0) (1 1 1) (5 5 5) masked @raw \"...\"" => "execute if blocks 0 0 0 1 1 1 5 5 5 masked run ...");
+    assert_e2e!("if data @s.SelectedItem @raw \"...\"" => "execute if data entity @s SelectedItem run ...");
+    assert_e2e!("unless data settings.enabled @raw \"...\"" => "execute unless data storage test settings.enabled run ...");
+    assert_e2e!("if data (~ ~ ~) \"Items[0]\" @raw \"...\"" => "execute if data block ~ ~ ~ Items[0] run ...");
+    assert_e2e!("if predicate is_raining @raw \"...\"" => "execute if predicate test:is_raining run ...");
+    assert_e2e!("if biome (~ ~ ~) \"minecraft:plains\" @raw \"...\"" => "execute if biome ~ ~ ~ minecraft:plains run ...");
+    assert_e2e!("unless dimension the_nether @raw \"...\"" => "execute unless dimension the_nether run ...");
+    assert_e2e!("if loaded (0 0 0) @raw \"...\"" => "execute if loaded 0 0 0 run ...");
+    assert_e2e!("if function check @raw \"...\"" => "execute if function test:check run ...");
+    assert_e2e!("if data = 1 @raw \"...\"" => "execute if score %data dummy matches 1 run ...");
+}
+
+#[test]
+fn positioning_blocks() {
+    assert_e2e!("in the_nether @raw \"...\"" => "execute in the_nether run ...");
+    assert_e2e!("align xz @raw \"...\"" => "execute align xz run ...");
+    assert_e2e!("positioned over world_surface @raw \"...\"" => "execute positioned over world_surface run ...");
+    assert_e2e!("positioned as @p @raw \"...\"" => "execute positioned as @p run ...");
+    assert_e2e!("in \"minecraft:the_end\" positioned as @r align xyz positioned over ocean_floor @raw \"...\"" =>
+        "execute in minecraft:the_end positioned as @r align xyz positioned over ocean_floor run ..."
+    );
+}
+
+#[test]
+fn tags() {
+    assert_e2e!("tag @s += marked" => "tag @s add marked");
+    assert_e2e!("tag @e[type=pig] -= [marked, \"seen\"]" => "tag @e[type=pig] remove marked\ntag @e[type=pig] remove seen");
+    assert_e2e!("if @s has marked @raw \"...\"" => "execute if entity @s[tag=marked] run ...");
+    assert_e2e!("unless @e[type=zombie] has !marked @raw \"...\"" => "execute unless entity @e[tag=!marked, type=zombie] run ...");
+
+    let inter = interpret_e2e!("function load [
+        tag @e[type=zombie, limit=1] += marked
+        if @s has marked @raw \"...\"
+        if @s has unmarked @raw \"...\"
+    ]");
+    // only `unmarked` gets a warning
+    assert_eq!(inter.tested_tags.difference(&inter.added_tags).collect::<Vec<_>>(), [&RStr::from("unmarked")]);
+}
+
+#[test]
+fn objectives() {
+    let compiled = build_e2e!("@objective jumps { criteria: \"minecraft.custom:minecraft.jump\", display: \"Jumps\", render: hearts, sidebar: true } @s:jumps = 0");
+    let load = compiled.functions.get("__load__").unwrap().base();
+    assert!(load.contains("\nscoreboard objectives add jumps minecraft.custom:minecraft.jump \"Jumps\"\nscoreboard objectives modify jumps rendertype hearts\nscoreboard objectives setdisplay sidebar jumps"), "{load}");
+
+    assert!(e2e_error!("@s:kills += 1", strict_objectives: true).contains("Objective `kills` is used but never declared"));
+    let strict = interpret_e2e!("x += 1 @objective kills {} @s:kills += 1", strict_objectives: true);
+    assert!(strict.objectives.contains_key("kills"));
+    // declarations count no matter where they are in the program
+    let declared_after = interpret_e2e!("function load @s:deaths += 1 @objective deaths { criteria: deathCount }", strict_objectives: true);
+    assert_eq!(&*declared_after.objectives["deaths"], "deathCount");
+}
+
+#[test]
+fn bossbars() {
+    let compiled = build_e2e!("@bossbar fight { name: \"Boss\", color: red, style: notched_10, max: 200, players: @a } function tick [
+        bossbar::fight.value = @e[tag=boss, limit=1]:hp
+        bossbar::fight.max = total
+        bossbar::fight.visible = @e[tag=boss]
+        bossbar::fight.visible = (phase > 1)
+        bossbar::fight.color = purple
+        remaining = bossbar::fight.value
+        bossbar::fight.value := @function \"count\"
+    ]");
+    let load = compiled.functions.get("__load__").unwrap().base();
+    assert!(load.contains("\nbossbar add test:fight {\"text\":\"Boss\"}\nbossbar set test:fight color red\nbossbar set test:fight max 200\nbossbar set test:fight players @a\nbossbar set test:fight style notched_10"), "{load}");
+    assert_eq!(compiled.functions.get("tick").unwrap().base().trim(), "execute store result bossbar test:fight value run scoreboard players get @e[limit=1, tag=boss] hp
+execute store result bossbar test:fight max run scoreboard players get %total dummy
+execute if entity @e[tag=boss] run bossbar set test:fight visible true
+execute unless entity @e[tag=boss] run bossbar set test:fight visible false
+execute unless score %phase dummy matches ..1 run bossbar set test:fight visible true
+execute if score %phase dummy matches ..1 run bossbar set test:fight visible false
+bossbar set test:fight color purple
+execute store result score %remaining dummy run bossbar get test:fight value
+execute store result bossbar test:fight value run function test:count");
+}
+
+#[test]
+fn teams() {
+    let compiled = build_e2e!("@team red { color: red, friendly_fire: false, collision: push_own_team, prefix: \"[R] \" } function start [
+        team red += @a[tag=red]
+        team red -= @a[tag=spectating]
+    ]");
+    let load = compiled.functions.get("__load__").unwrap().base();
+    assert!(load.contains("\nteam add red \"red\"\nteam modify red collisionRule pushOwnTeam\nteam modify red color red\nteam modify red friendlyFire false\nteam modify red prefix {\"text\":\"[R] \"}"), "{load}");
+    assert_eq!(compiled.functions.get("start").unwrap().base().trim(), "team join red @a[tag=red]
+team leave @a[tag=spectating, team=red]");
+    let uninstall = compiled.functions.get("__uninstall__").unwrap().base();
+    assert!(uninstall.contains("scoreboard objectives remove dummy\n"), "{uninstall}");
+    assert!(uninstall.contains("team remove red\n"), "{uninstall}");
+}
+
+#[test]
+fn titles() {
+    let compiled = build_e2e!("function hud [
+        @title_times { fade_in: 5, stay: 40 }
+        title @a { \"Round \", round }
+        subtitle @a { [\"Fight!\", bold, color: red] }
+        actionbar @s { \"Health: \", @s:hp }
+        title = 1
+    ]");
+    assert_eq!(compiled.functions.get("hud").unwrap().base().trim(), "title @a times 5 40 20
+title @a title [{\"text\":\"Round \"},{\"score\":{\"name\":\"%round\",\"objective\":\"dummy\"}}]
+title @a subtitle [{\"bold\":true,\"color\":\"red\",\"text\":\"Fight!\"}]
+title @s actionbar [{\"text\":\"Health: \"},{\"score\":{\"name\":\"@s\",\"objective\":\"hp\"}}]
+scoreboard players set %title dummy 1");
+}
+
+#[test]
+fn particles() {
+    let compiled = build_e2e!("function spell [
+        @particle flame
+        @particle { kind: dust, color: [1, 0, 0.5], size: 2, pos: (~ ~1 ~), count: 3, viewers: @a }
+        @particle { kind: end_rod, shape: circle, radius: 2, points: 4, pos: (^ ^ ^1) }
+        @particle { kind: block, block: \"oak_log[axis=y]\", force: true }
+    ]");
+    let spell = compiled.functions.get("spell").unwrap();
+    assert_eq!(spell.base().trim(), "particle flame ~ ~ ~ 0 0 0 0 1
+particle dust 1 0 0.5 2 ~ ~1 ~ 0 0 0 0 3 normal @a
+particle end_rod ^2 ^ ^1 0 0 0 0 1
+particle end_rod ^ ^ ^3 0 0 0 0 1
+particle end_rod ^-2 ^ ^1 0 0 0 0 1
+particle end_rod ^ ^ ^-1 0 0 0 0 1
+particle block oak_log[axis=y] ~ ~ ~ 0 0 0 0 1 force");
+    let modern = spell.versions().get(&41).unwrap();
+    assert!(modern.contains("particle dust{color: [1f, 0f, 0.5f], scale: 2f} ~ ~1 ~ 0 0 0 0 3 normal @a"), "{modern}");
+    assert!(modern.contains("particle block{block_state: {Name: \"oak_log\", Properties: {axis: \"y\"}}} ~ ~ ~ 0 0 0 0 1 force"), "{modern}");
+}
+
+#[test]
+fn typed_summon() {
+    let compiled = build_e2e!("function spawn [
+        summon zombie (~ ~1 ~) { nbt: { NoAI: true, Health: 40.0 }, tags: [boss, \"wave_1\"] } {
+            hp = 40
+            @s:hp = 40
+        }
+        summon armor_stand (^ ^ ^2)
+        summon pig { tags: piggy }
+        summon sheep @raw \"say baa\"
+    ]");
+    let spawn = compiled.functions.get("spawn").unwrap().base();
+    let marker = lazy_regex!("__summoned_[0-9a-f]+").find(spawn).unwrap().as_str();
+    let func = lazy_regex!("function test:(__internal__/summon_[0-9a-f]+)").captures(spawn).unwrap()[1].to_string();
+    assert_eq!(spawn.trim(), format!("summon zombie ~ ~1 ~ {{Health: 40f, NoAI: true, Tags: [\"boss\", \"wave_1\", \"{marker}\"]}}
+execute as @e[limit=1, tag={marker}] at @s run function test:{func}
+summon armor_stand ^ ^ ^2
+summon pig ~ ~ ~ {{Tags: [\"piggy\"]}}
+execute summon sheep run say baa"));
+    assert_eq!(compiled.functions.get(&*func).unwrap().base().trim(), format!("tag @s remove {marker}
+scoreboard players set %hp dummy 40
+scoreboard players set @s hp 40"));
+}
+
+#[test]
+fn block_placement() {
+    let compiled = build_e2e!("function build [
+        @setblock { pos: (~ ~1 ~), block: chest, states: { facing: north }, nbt: { Lock: \"key\" } }
+        @fill { from: (~-2 ~ ~-2), to: (~2 ~3 ~2), block: glass, mode: hollow }
+        @fill { from: (0 0 0), to: (9 9 9), block: air, filter: \"#minecraft:logs\" }
+        @clone { from: (0 64 0), to: (4 68 4), destination: (~ ~ ~), mask: masked, mode: move }
+    ]");
+    assert_eq!(compiled.functions.get("build").unwrap().base().trim(), "setblock ~ ~1 ~ chest[facing=north]{Lock: \"key\"}
+fill ~-2 ~ ~-2 ~2 ~3 ~2 glass hollow
+fill 0 0 0 9 9 9 air replace #minecraft:logs
+clone 0 64 0 4 68 4 ~ ~ ~ masked move");
+}
+
+#[test]
+fn large_fills() {
+    // 64 * 10 * 64 blocks splits into 2 * 1 * 2 cubes
+    let compiled = build_e2e!("function floor [ @fill { from: (0 0 0), to: (63 9 63), block: stone } ]");
+    assert_eq!(compiled.functions.get("floor").unwrap().base().trim(), "fill 0 0 0 31 9 31 stone
+fill 0 0 32 31 9 63 stone
+fill 32 0 0 63 9 31 stone
+fill 32 0 32 63 9 63 stone");
+    // a hollow 40³ box keeps its shell and clears its inside, without any fill over the limit
+    let compiled = build_e2e!("function box [ @fill { from: (~ ~ ~), to: (~39 ~39 ~39), block: glass, mode: hollow } ]");
+    let fills = compiled.functions.get("box").unwrap().base().trim().to_string();
+    let mut volume = 0;
+    for line in fills.lines() {
+        let parts: Vec<i64> = line.split(' ').skip(1).take(6).map(|c| c.trim_start_matches('~').parse().unwrap_or(0)).collect();
+        let size = (parts[3] - parts[0] + 1) * (parts[4] - parts[1] + 1) * (parts[5] - parts[2] + 1);
+        assert!(size <= 32768, "{line}");
+        volume += size;
+    }
+    assert_eq!(volume, 40 * 40 * 40);
+    assert!(fills.contains("fill ~1 ~1 ~1 ~32 ~32 ~32 air"), "{fills}");
+    // moving a region onto itself copies the pieces furthest along first
+    let compiled = build_e2e!("function shift [ @clone { from: (0 0 0), to: (63 63 9), destination: (8 0 0), mode: force } ]");
+    assert_eq!(compiled.functions.get("shift").unwrap().base().trim(), "clone 32 0 0 63 31 9 40 0 0 replace force
+clone 32 32 0 63 63 9 40 32 0 replace force
+clone 0 0 0 31 31 9 8 0 0 replace force
+clone 0 32 0 31 63 9 8 32 0 replace force");
+}
+
+#[test]
+fn scheduling() {
+    let compiled = build_e2e!("function start [
+        @schedule tick_later in 5s
+        @schedule \"utils/cleanup\" in 1d append
+        @schedule respawn in 1.5s replace
+        @schedule 40 { @raw \"say two seconds\" }
+        @unschedule tick_later
+    ]");
+    let start = compiled.functions.get("start").unwrap().base();
+    let anonymous = lazy_regex!("schedule function test:(__internal__/schedule_[0-9a-f]+) 40 replace").captures(start).unwrap()[1].to_string();
+    assert_eq!(start.trim(), format!("schedule function test:tick_later 100 replace
+schedule function test:utils/cleanup 24000 append
+schedule function test:respawn 30 replace
+schedule function test:{anonymous} 40 replace
+schedule clear test:tick_later"));
+    assert_eq!(compiled.functions.get(&*anonymous).unwrap().base().trim(), "say two seconds");
+}
+
+#[test]
+fn attributes() {
+    let compiled = build_e2e!("function stats [
+        @s::attr.generic.max_health = 40
+        hp = @s::attr.generic.max_health
+        speed .= @s::attr.generic.movement_speed
+        @s::attr.generic.attack_damage += { name: \"sword_bonus\", amount: 2.5, operation: multiply_base }
+        @s::attr.generic.attack_damage -= \"sword_bonus\"
+    ]");
+    let stats = compiled.functions.get("stats").unwrap();
+    let base = stats.base();
+    // the UUID has to stay the same between compiler versions so old modifiers can be removed
+    let uuid = "3bffcca1-dc9e-4028-9d07-b502db8ec1b0";
+    assert_eq!(base.trim(), format!("attribute @s generic.max_health base set 40
+execute store result score %hp dummy run attribute @s generic.max_health get
+execute store result score %speed dummy run attribute @s generic.movement_speed get 100
+attribute @s generic.attack_damage modifier add {uuid} \"sword_bonus\" 2.5 multiply_base
+attribute @s generic.attack_damage modifier remove {uuid}"));
+    assert_eq!(stats.versions().get(&48).unwrap().trim(), "attribute @s generic.max_health base set 40
+execute store result score %hp dummy run attribute @s generic.max_health get
+execute store result score %speed dummy run attribute @s generic.movement_speed get 100
+attribute @s generic.attack_damage modifier add test:sword_bonus 2.5 add_multiplied_base
+attribute @s generic.attack_damage modifier remove test:sword_bonus");
+    assert_eq!(stats.versions().get(&57).unwrap().trim(), "attribute @s max_health base set 40
+execute store result score %hp dummy run attribute @s max_health get
+execute store result score %speed dummy run attribute @s movement_speed get 100
+attribute @s attack_damage modifier add test:sword_bonus 2.5 add_multiplied_base
+attribute @s attack_damage modifier remove test:sword_bonus");
+    assert_e2e!("@e[type=zombie]::attr.generic.max_health = 40" => "execute as @e[type=zombie] run attribute @s generic.max_health base set 40");
+    assert_eq!(e2e_error!("x = @e[type=zombie]::attr.generic.max_health"), "Can't read `@e[type=zombie]::attr.generic.max_health` from more than one entity; add `limit=1`");
+}
+
+#[test]
+fn pseudo_properties() {
+    let compiled = build_e2e!("function physics [
+        height = @s::y
+        speed .= @s::motion_y
+        hunger = @s::food
+        @s::y += 10
+        @a::yaw = 90
+        @e[type=pig]::health = 4
+        @e[tag=ball,limit=1]::motion_y .= speed
+        @e[tag=ball,limit=1]::x := @function \"get_x\"
+        precise = @s::x(1000)
+        @e[tag=ball]::motion_x(1000) = precise
+    ]");
+    assert_eq!(compiled.functions.get("physics").unwrap().base().trim(), "execute store result score %height dummy run data get entity @s Pos[1] 1
+execute store result score %speed dummy run data get entity @s Motion[1] 100
+execute store result score %hunger dummy run data get entity @s foodLevel 1
+execute at @s run tp @s ~ ~10 ~
+execute as @a at @s run tp @s ~ ~ ~ 90 ~
+execute as @e[type=pig] run data modify entity @s Health set value 4f
+execute store result entity @e[limit=1, tag=ball] Motion[1] double 0.01 run scoreboard players get %speed dummy
+execute store result entity @e[limit=1, tag=ball] Pos[0] double 1 run function test:get_x
+execute store result score %precise dummy run data get entity @s Pos[0] 1000
+execute as @e[tag=ball] store result entity @s Motion[0] double 0.001 run scoreboard players get %precise dummy");
+    assert_eq!(e2e_error!("function f [ y = @e[type=pig]::y ]"), "Can't read `@e[type=pig]::y` from more than one entity; add `limit=1`");
+}
+
+#[test]
+fn write_overlays() {
+    let compiled = build_e2e!("function stats [ @s::attr.generic.max_health = 40 ]");
+    let parent = format!("{}/minescript_write_overlays_{}/", std::env::temp_dir().display(), std::process::id());
+    crate::compiler::write(&compiled, &parent, "test").unwrap();
+    let read = |path: &str| std::fs::read_to_string(format!("{parent}test/{path}")).unwrap();
+    assert_eq!(read("data/test/functions/stats.mcfunction").trim(), "attribute @s generic.max_health base set 40");
+    // the renamed folders get a full copy of the pack, with the newest version of each file
+    assert_eq!(read("fmt_48/data/test/function/stats.mcfunction").trim(), "attribute @s generic.max_health base set 40");
+    assert_eq!(read("fmt_57/data/test/function/stats.mcfunction").trim(), "attribute @s max_health base set 40");
+    let mcmeta = read("pack.mcmeta");
+    assert!(mcmeta.contains("{\"directory\":\"fmt_57\",\"formats\":{\"max_inclusive\":57,\"min_inclusive\":57}}"), "{mcmeta}");
+    assert!(mcmeta.contains("\"supported_formats\":{\"max_inclusive\":57,\"min_inclusive\":15}"), "{mcmeta}");
+    std::fs::remove_dir_all(&parent).unwrap();
+}
+
+#[test]
+fn item_properties() {
+    let compiled = build_e2e!("@item {
+        name: \"Sword\"
+        base: \"diamond_sword\"
+        lore: [\"Sharp\", {text: \"Very sharp\", color: \"red\"}]
+        enchantments: {sharpness: 5}
+        attributes: [{attribute: \"generic.attack_damage\", amount: 4, slot: mainhand, name: \"sword_damage\"}]
+        unbreakable: true
+        glint: true
+        hide: [enchantments, unbreakable]
+    }");
+    let give = compiled.functions.get("give/sword").unwrap();
+    let base = give.base();
+    assert!(base.contains("Enchantments: [{id: \"minecraft:sharpness\", lvl: 5}], HideFlags: 5, Unbreakable: 1b"));
+    assert!(base.contains("Lore: [\"{\\\"text\\\":\\\"Sharp\\\"}\", \"{\\\"color\\\":\\\"red\\\",\\\"text\\\":\\\"Very sharp\\\"}\"]"));
+    assert!(base.contains("AttributeModifiers: [{Amount: 4f, AttributeName: \"generic.attack_damage\", Name: \"sword_damage\", Operation: 0, Slot: \"mainhand\", UUID: [I; "));
+    let components = give.versions().get(&41).unwrap();
+    assert!(components.starts_with("give @s minecraft:diamond_sword[attribute_modifiers={modifiers: [{amount: 4f, name: \"sword_damage\", operation: \"add_value\", slot: \"mainhand\", type: \"generic.attack_damage\", uuid: [I; "));
+    assert!(components.contains(",enchantment_glint_override=true,enchantments={levels: {sharpness: 5}, show_in_tooltip: false},lore=["));
+    assert!(components.ends_with(",unbreakable={show_in_tooltip: false}]"));
+    assert!(give.versions().get(&48).unwrap().contains("{amount: 4f, id: \"test:sword_damage\", operation: \"add_value\", slot: \"mainhand\", type: \"generic.attack_damage\"}"));
+    assert!(give.versions().get(&57).unwrap().contains("type: \"attack_damage\""));
+}
+
+#[test]
+fn item_components() {
+    let compiled = build_e2e!("@item {
+        name: \"Wand\"
+        base: \"stick\"
+        on_consume: @raw \"say consumed\"
+        while_slot: { offhand: @raw \"say in offhand\" }
+        recipe: @shapeless [\"stick\", \"diamond\"]
+    }");
+    let give = compiled.functions.get("give/wand").unwrap();
+    assert_eq!(give.base(), "give @s minecraft:stick{display: {Name: \"{\\\"text\\\":\\\"Wand\\\",\\\"italic\\\":\\\"false\\\"}\"}, tag: {_is_minescript: \"Wand\"}}");
+    assert_eq!(give.versions().get(&41).unwrap(), "give @s minecraft:stick[custom_data={_is_minescript: \"Wand\"},custom_name=\"{\\\"italic\\\":false,\\\"text\\\":\\\"Wand\\\"}\"]");
+    let tick = compiled.functions.get("__tick__").unwrap();
+    assert!(tick.base().contains("@a[nbt={Inventory: [{Slot: -106b, tag: {tag: {_is_minescript: \"Wand\"}}}]}]"));
+    assert!(tick.versions().get(&41).unwrap().contains("@a[nbt={Inventory: [{Slot: -106b, components: {\"minecraft:custom_data\": {_is_minescript: \"Wand\"}}, id: \"minecraft:stick\"}]}]"));
+    let consume = compiled.advancements.get("consume/wand").unwrap();
+    assert!(consume.base().contains(r#""item":{"items":["minecraft:stick"],"nbt":"{\"tag\":{\"_is_minescript\":\"Wand\"}}"}"#));
+    assert!(consume.versions().get(&41).unwrap().contains(r#""item":{"items":["minecraft:stick"],"predicates":{"minecraft:custom_data":"{_is_minescript: \"Wand\"}"}}"#));
+    let recipe = compiled.recipes.get("wand").unwrap();
+    assert!(recipe.base().contains(r#""result":{"count":1,"item":"minecraft:knowledge_book"}"#));
+    assert!(recipe.versions().get(&41).unwrap().contains(r#""result":{"count":1,"id":"minecraft:knowledge_book"}"#));
+}
+
+#[test]
+#[allow(clippy::literal_string_with_formatting_args)]
+fn item_melee_triggers() {
+    let compiled = build_e2e!("@item {
+        name: \"Dagger\"
+        base: \"iron_sword\"
+        on_hit: @raw \"effect give @e[tag=dagger_victim] poison 5\"
+        on_kill: @raw \"say got one\"
+        on_hurt: @raw \"say ouch\"
+        on_block_break: @raw \"say mined\"
+    }");
+    // only the entities the holder hurt are victims, not everything hurt this tick
+    assert_eq!(compiled.functions.get("hit/dagger").unwrap().base(), "advancement revoke @s only test:hit/dagger
+tag @s add dagger_attacker
+execute as @e[distance=..8, nbt={HurtTime:10s}] store success score @s hit_dagger on attacker if entity @s[tag=dagger_attacker]
+tag @e[distance=..8, scores={hit_dagger=1}] add dagger_victim
+scoreboard players reset @e[distance=..8] hit_dagger
+effect give @e[tag=dagger_victim] poison 5
+tag @e[tag=dagger_victim] remove dagger_victim
+tag @s remove dagger_attacker");
+    assert_eq!(compiled.functions.get("kill/dagger").unwrap().base(), "advancement revoke @s only test:kill/dagger\nsay got one");
+    let hit = compiled.advancements.get("hit/dagger").unwrap();
+    assert_eq!(hit.base(), r#"{"criteria":{"requirement":{"conditions":{"player":{"equipment":{"mainhand":{"items":["minecraft:iron_sword"],"nbt":"{\"tag\":{\"_is_minescript\":\"Dagger\"}}"}}}},"trigger":"minecraft:player_hurt_entity"}},"rewards":{"function":"test:hit/dagger"}}"#);
+    assert!(hit.versions().get(&41).unwrap().contains(r#""mainhand":{"items":["minecraft:iron_sword"],"predicates":{"minecraft:custom_data":"{_is_minescript: \"Dagger\"}"}}"#));
+    assert!(compiled.advancements.get("kill/dagger").unwrap().base().contains("minecraft:player_killed_entity"));
+    assert!(compiled.advancements.get("hurt/dagger").unwrap().base().contains("minecraft:entity_hurt_player"));
+    let tick = compiled.functions.get("__tick__").unwrap().base();
+    // hitting an entity also counts as using a sword, so that doesn't count as breaking a block
+    assert!(tick.contains("execute as @a[scores={mine_iron_sword=1}, tag=holding_dagger] at @s if entity @s[tag=!dagger_melee] run say mined\ntag @a remove dagger_melee"), "{tick}");
+    assert_eq!(compiled.functions.get("melee/dagger").unwrap().base(), "advancement revoke @s only test:melee/dagger\ntag @s add dagger_melee");
+    assert!(compiled.advancements.get("melee/dagger").unwrap().base().contains("minecraft:player_hurt_entity"));
+    assert!(tick.contains("scoreboard players reset @a mine_iron_sword"));
+    assert!(compiled.functions.get("__load__").unwrap().base().contains("scoreboard objectives add hit_dagger dummy"));
+}
+
+#[test]
+fn item_equip_events() {
+    let compiled = build_e2e!("@item {
+        name: \"Crown\"
+        base: \"golden_helmet\"
+        on_equip: { head: @raw \"effect give @s regeneration infinite\" }
+        on_unequip: { head: @raw \"effect clear @s regeneration\", mainhand: @raw \"say put away\" }
+    }");
+    let tick = compiled.functions.get("__tick__").unwrap();
+    assert_eq!(tick.base().trim(), "execute as @a[nbt={Inventory: [{Slot: 103b, tag: {tag: {_is_minescript: \"Crown\"}}}]}, tag=!equipped_crown_head] at @s run function test:equip/crown/head
+execute as @a[nbt=!{Inventory: [{Slot: 103b, tag: {tag: {_is_minescript: \"Crown\"}}}]}, tag=equipped_crown_head] at @s run function test:unequip/crown/head
+execute as @a[nbt={SelectedItem: {id: \"minecraft:golden_helmet\", tag: {tag: {_is_minescript: \"Crown\"}}}}, tag=!equipped_crown_mainhand] at @s run tag @s add equipped_crown_mainhand
+execute as @a[nbt=!{SelectedItem: {id: \"minecraft:golden_helmet\", tag: {tag: {_is_minescript: \"Crown\"}}}}, tag=equipped_crown_mainhand] at @s run function test:unequip/crown/mainhand");
+    assert!(tick.versions().get(&41).unwrap().contains("execute as @a[nbt=!{Inventory: [{Slot: 103b, components: {\"minecraft:custom_data\": {_is_minescript: \"Crown\"}}, id: \"minecraft:golden_helmet\"}]}, tag=equipped_crown_head] at @s run function test:unequip/crown/head"));
+    assert_eq!(compiled.functions.get("equip/crown/head").unwrap().base().trim(), "tag @s add equipped_crown_head\neffect give @s regeneration infinite");
+    assert_eq!(compiled.functions.get("unequip/crown/head").unwrap().base().trim(), "tag @s remove equipped_crown_head\neffect clear @s regeneration");
+}
+
+#[test]
+fn item_cooldowns() {
+    let compiled = build_e2e!("@item {
+        name: \"Wand\"
+        base: \"carrot_on_a_stick\"
+        on_use: @raw \"say zap\"
+        cooldown: 2s
+        on_cooldown: @raw \"say not yet\"
+        show_cooldown: true
+    }");
+    assert_eq!(compiled.functions.get("use/wand").unwrap().base().trim(), "execute if score @s cd_wand matches 1.. run say not yet
+execute unless score @s cd_wand matches 1.. run function test:cooldown/wand/ready");
+    assert_eq!(compiled.functions.get("cooldown/wand/ready").unwrap().base().trim(), "scoreboard players set @s cd_wand 40\nsay zap");
+    let tick = compiled.functions.get("__tick__").unwrap().base();
+    assert!(tick.contains(r#"execute as @a[scores={cd_wand=1..}] run title @s actionbar [{"text":"Wand "},{"score":{"name":"@s","objective":"cd_wand"}},{"text":"t"}]
+scoreboard players remove @a[scores={cd_wand=1..}] cd_wand 1"#));
+    assert!(compiled.functions.get("__load__").unwrap().base().contains("scoreboard objectives add cd_wand dummy"));
+}
+
+#[test]
+fn item_durability() {
+    let compiled = build_e2e!("@item {
+        name: \"Wand\"
+        base: \"carrot_on_a_stick\"
+        lore: [\"Zaps things\"]
+        on_use: @raw \"say zap\"
+        durability: 250
+        on_break: @raw \"say the wand broke\"
+    }");
+    assert_eq!(compiled.functions.get("use/wand").unwrap().base().trim(), "say zap\nfunction test:durability/wand");
+    assert_eq!(compiled.functions.get("durability/wand").unwrap().base(), "execute store result score @s dur_wand run data get entity @s SelectedItem.tag.tag.durability
+scoreboard players remove @s dur_wand 1
+execute store result storage test:durability __durability__ int 1 run scoreboard players get @s dur_wand
+execute if score @s dur_wand matches 1.. run item modify entity @s weapon.mainhand test:durability/wand
+execute if score @s dur_wand matches ..0 run function test:durability/wand/break");
+    assert!(compiled.functions.get("durability/wand").unwrap().get(41).starts_with(r#"execute store result score @s dur_wand run data get entity @s SelectedItem.components."minecraft:custom_data".durability"#));
+    assert_eq!(compiled.functions.get("durability/wand/break").unwrap().base(), "item replace entity @s weapon.mainhand with air
+playsound minecraft:entity.item.break player @a ~ ~ ~
+say the wand broke");
+    let give = compiled.functions.get("give/wand").unwrap();
+    assert!(give.base().contains("tag: {_is_minescript: \"Wand\", durability: 250}"));
+    assert!(give.get(41).contains("custom_data={_is_minescript: \"Wand\", durability: 250}"));
+    assert!(give.get(41).contains("Durability: 250 / 250"));
+    let modifier = compiled.item_modifiers.get("durability/wand").unwrap();
+    assert!(modifier.base().contains(r#"{"function":"minecraft:copy_nbt","ops":[{"op":"replace","source":"__durability__","target":"tag.durability"}]"#));
+    assert!(modifier.base().contains(r#"{"damage":{"scale":0.004,"score":"dur_wand","target":"this","type":"minecraft:score"},"function":"minecraft:set_damage"}"#));
+    assert!(modifier.get(41).contains(r#"{"function":"minecraft:copy_custom_data""#));
+    // the user's lore is kept above the durability line
+    assert!(modifier.base().contains(r#"{"entity":"this","function":"minecraft:set_lore","lore":[{"text":"Zaps things"},[{"color":"gray","italic":false,"text":"Durability: "},{"score":{"name":"@s","objective":"dur_wand"}},{"text":" / 250"}]],"replace":true}"#));
+    assert!(modifier.get(41).contains(r#"{"entity":"this","function":"minecraft:set_lore","lore":[[{"color":"gray","italic":false,"text":"Durability: "},{"score":{"name":"@s","objective":"dur_wand"}},{"text":" / 250"}]],"mode":"replace_section","offset":1}"#));
+    assert!(modifier.base().contains(r#""source":"test:durability","type":"minecraft:storage""#));
+}
+
+#[test]
+fn item_drop_and_pickup() {
+    let compiled = build_e2e!("@item {
+        name: \"Grenade\"
+        base: \"snowball\"
+        on_drop: @raw \"say dropped\"
+        on_pickup: @raw \"say picked up\"
+    }");
+    let tick = compiled.functions.get("__tick__").unwrap();
+    assert!(tick.base().contains("execute as @a[scores={drop_snowball=1..}] at @s run function test:drop/grenade"));
+    assert!(tick.base().contains("execute as @a at @s run function test:pickup/grenade"));
+    assert!(tick.base().contains("scoreboard players reset @a drop_snowball"));
+    assert!(tick.base().contains("scoreboard players reset @a pickup_snowball"));
+    let drop = compiled.functions.get("drop/grenade").unwrap();
+    assert_eq!(drop.base(), r#"tag @e[type=item, distance=..3, nbt={Age:0s,Item:{id:"minecraft:snowball",tag:{tag: {_is_minescript: "Grenade"}}}}] add grenade_dropped
+execute if entity @e[tag=grenade_dropped] run say dropped
+tag @e[tag=grenade_dropped] remove grenade_dropped"#);
+    assert!(drop.get(41).starts_with(r#"tag @e[type=item, distance=..3, nbt={Age:0s,Item:{components: {"minecraft:custom_data": {_is_minescript: "Grenade"}}, id: "minecraft:snowball"}}] add grenade_dropped"#));
+    // picking up a vanilla snowball doesn't change how many grenades the player has
+    let pickup = compiled.functions.get("pickup/grenade").unwrap();
+    assert_eq!(pickup.base(), r#"execute store result score %__count__ has_grenade run clear @s minecraft:snowball{tag: {_is_minescript: "Grenade"}} 0
+scoreboard players operation %__count__ has_grenade >< @s has_grenade
+execute if entity @s[scores={pickup_snowball=1..}] if score @s has_grenade > %__count__ has_grenade run say picked up"#);
+    assert!(pickup.get(41).starts_with(r#"execute store result score %__count__ has_grenade run clear @s minecraft:snowball[minecraft:custom_data~{_is_minescript: "Grenade"}] 0"#));
+    let load = compiled.functions.get("__load__").unwrap().base();
+    assert!(load.contains("scoreboard objectives add drop_snowball minecraft.dropped:minecraft.snowball"));
+    assert!(load.contains("scoreboard objectives add pickup_snowball minecraft.picked_up:minecraft.snowball"));
+    assert!(load.contains("scoreboard objectives add has_grenade dummy"));
+}
+
+#[test]
+fn custom_blocks() {
+    let compiled = build_e2e!("@block {
+        name: \"Grinder\"
+        model: \"block/grinder\"
+        on_place: @raw \"say placed\"
+        on_break: @raw \"say broken\"
+        on_interact: @raw \"say clicked\"
+        tick: @raw \"particle crit ~ ~ ~\"
+    }");
+    let give = compiled.functions.get("give/grinder").unwrap();
+    assert!(give.base().starts_with(r#"give @s minecraft:item_frame{EntityTag: {Invisible: true, Tags: ["place_grinder"], id: "minecraft:item_frame"}, "#));
+    assert!(give.get(41).contains(r#"entity_data={Invisible: true, Tags: ["place_grinder"], id: "minecraft:item_frame"}"#));
+    assert!(give.get(41).contains("custom_model_data="));
+    let tick = compiled.functions.get("__tick__").unwrap().base();
+    assert_eq!(tick.trim(), "execute as @e[type=item_frame, tag=place_grinder] at @s align xyz positioned ~0.5 ~0.5 ~0.5 run function test:block/grinder/place
+execute as @e[type=item_display, tag=block_grinder] at @s unless block ~ ~ ~ minecraft:spawner run function test:block/grinder/break
+scoreboard players reset @a mined_grinder
+execute as @e[tag=block_grinder, type=item_display] at @s run particle crit ~ ~ ~");
+    let place = compiled.functions.get("block/grinder/place").unwrap();
+    assert!(place.base().starts_with(r#"setblock ~ ~ ~ minecraft:spawner
+summon item_display ~ ~ ~ {Tags:["block_grinder","block_grinder_placed"],item:{id:"minecraft:item_frame",Count:1b,tag:{"#));
+    assert!(place.get(41).contains(r#"item:{id:"minecraft:item_frame",count:1,components:{"minecraft:custom_data":{_is_minescript: "Grinder"},"#));
+    assert!(place.base().ends_with("}}}}
+kill @s
+execute as @e[tag=block_grinder_placed] at @s run say placed
+tag @e[tag=block_grinder_placed] remove block_grinder_placed"));
+    let break_fn = compiled.functions.get("block/grinder/break").unwrap();
+    assert!(break_fn.base().starts_with(r#"say broken
+kill @e[type=item, distance=..1, nbt={Age:0s,Item:{id:"minecraft:spawner"}}]
+execute if entity @a[distance=..8, scores={mined_grinder=1..}] run summon item ~ ~ ~ {Item:{id:"minecraft:item_frame",Count:1b,tag:{"#));
+    assert!(break_fn.get(41).contains(r#"summon item ~ ~ ~ {Item:{id:"minecraft:item_frame",count:1,components:{"#));
+    assert!(break_fn.base().ends_with("}}}}\nkill @s"));
+    assert!(compiled.functions.get("__load__").unwrap().base().contains("scoreboard objectives add mined_grinder minecraft.mined:minecraft.spawner"));
+    // right clicks come from an advancement, and a ray finds which block was clicked
+    assert_eq!(compiled.functions.get("block/grinder/interact").unwrap().base(), "advancement revoke @s only test:block/grinder/interact
+tag @s add grinder_interacting
+execute anchored eyes positioned ^ ^ ^ run function test:block/grinder/ray
+tag @s remove grinder_interacting");
+    assert_eq!(compiled.functions.get("block/grinder/ray").unwrap().base(), "execute if block ~ ~ ~ minecraft:spawner align xyz positioned ~0.5 ~0.5 ~0.5 as @e[distance=..0.1, limit=1, tag=block_grinder, type=item_display] run say clicked
+execute unless block ~ ~ ~ minecraft:spawner if entity @s[distance=..6] positioned ^ ^ ^0.1 run function test:block/grinder/ray");
+    let advancement = compiled.advancements.get("block/grinder/interact").unwrap();
+    assert_eq!(advancement.base(), r#"{"criteria":{"with_item":{"conditions":{"location":[{"condition":"minecraft:location_check","predicate":{"block":{"blocks":["minecraft:spawner"]}}}]},"trigger":"minecraft:item_used_on_block"}},"rewards":{"function":"test:block/grinder/interact"}}"#);
+    assert!(advancement.get(48).contains(r#""trigger":"minecraft:default_block_use""#));
+    assert!(advancement.get(48).contains(r#""requirements":[["with_item","with_hand"]]"#));
+    assert!(!compiled.functions.contains_key("block/grinder/hit"));
+    // both the block's loot and the block itself, from silk touch, are swapped for the item
+    let compiled = build_e2e!("@block { name: \"Quarry\", block: stone }");
+    assert!(compiled.functions.get("block/quarry/break").unwrap().base().starts_with(r#"kill @e[type=item, distance=..1, nbt={Age:0s,Item:{id:"minecraft:stone"}}]
+kill @e[type=item, distance=..1, nbt={Age:0s,Item:{id:"minecraft:cobblestone"}}]
+execute if entity @a[distance=..8, scores={mined_quarry=1..}] run summon item ~ ~ ~ {Item:"#));
+    assert_eq!(e2e_error!("@block { name: \"Chest\", block: chest }"), "Block `block` must be a vanilla block with a known drop, like spawner, stone, or oak_planks; got `minecraft:chest`");
+}
@@ -78,6 +78,42 @@ fn for_loop() {
     );
 }
 
+#[test]
+fn for_range() {
+    // for i in 0..<@s:count step 2 {}
+    assert_eq!(
+        parse(vec![
+            Token::Identifier("for".into()),
+            Token::Identifier("i".into()),
+            Token::Identifier("in".into()),
+            Token::Range(Some(0), None),
+            Token::LCaret,
+            Token::At,
+            Token::Identifier("s".into()),
+            Token::Colon,
+            Token::Identifier("count".into()),
+            Token::Identifier("step".into()),
+            Token::Integer(2),
+            Token::LParen,
+            Token::RParen
+        ]),
+        Ok(Syntax::Block(
+            BlockType::For,
+            Box::new(Syntax::BinaryOp {
+                lhs: Box::new(Syntax::Identifier("i".into())),
+                operation: Operation::In,
+                rhs: Box::new(Syntax::ForRange {
+                    start: Box::new(Syntax::Integer(0)),
+                    end: Box::new(Syntax::SelectorColon(Selector::s(), "count".into())),
+                    inclusive: false,
+                    step: Some(2)
+                })
+            }),
+            Box::new(Syntax::Unit)
+        ))
+    );
+}
+
 #[test]
 fn coords() {
     // (^ ^2 ^1.5)
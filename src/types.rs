@@ -22,9 +22,9 @@ pub mod prelude {
     use std::rc::Rc;
 
     pub use super::command::{Command, Coordinate};
-    pub use super::execute::ExecuteOption;
+    pub use super::execute::{namespaced, ExecuteOption};
     pub use super::nbt::{Nbt, NbtLocation, NbtPathPart};
-    pub use super::repr::{CompiledRepr, InterRepr, Item};
+    pub use super::repr::{Block, CompiledRepr, InterRepr, Item, ObjectiveDisplay, ITEM_COMPONENT_VERSION};
     pub use super::selector::{Selector, SelectorType};
     pub use super::syntax::{BlockType, DataLocation, Operation, Syntax};
     pub use super::token::Token;
@@ -53,7 +53,7 @@ pub mod prelude {
                 }
                 NbtPathPart::Index(idx) => {
                     ret_buf.push('[');
-                    ret_buf.push_str(&format!("{idx}"));
+                    ret_buf.push_str(&idx.to_string());
                     ret_buf.push(']');
                 }
             }
@@ -1,4 +1,7 @@
-use std::{fmt::Display, hash::Hash};
+use std::{
+    fmt::{Display, Write as _},
+    hash::Hash,
+};
 
 use super::{nbt::NbtLocation, prelude::*};
 
@@ -9,6 +12,19 @@ pub enum Command {
     Raw(RStr),
     /// A tellraw command
     TellRaw(Selector<String>, RStr),
+    /// show a title, subtitle, or actionbar to the target
+    Title {
+        target: Selector<String>,
+        kind: RStr,
+        text: RStr,
+    },
+    /// set how long titles take to fade in, stay, and fade out, in ticks
+    TitleTimes {
+        target: Selector<String>,
+        fade_in: i32,
+        stay: i32,
+        fade_out: i32,
+    },
     /// give a target an effect. Duration defaults to infinite, level defaults to 1
     EffectGive {
         target: Selector<String>,
@@ -26,6 +42,35 @@ pub enum Command {
         time: i32,
         replace: bool,
     },
+    /// cancel every scheduled run of a function
+    ScheduleClear(RStr),
+    /// get the value of an attribute, multiplied by a scale
+    AttributeGet {
+        target: Selector<String>,
+        attribute: RStr,
+        scale: i32,
+    },
+    /// set the base value of an attribute
+    AttributeBaseSet {
+        target: Selector<String>,
+        attribute: RStr,
+        value: f32,
+    },
+    /// add a modifier to an attribute; older versions identify it by a UUID and name
+    AttributeModifierAdd {
+        target: Selector<String>,
+        attribute: RStr,
+        id: RStr,
+        name: Option<RStr>,
+        amount: f32,
+        operation: RStr,
+    },
+    /// remove a modifier from an attribute
+    AttributeModifierRemove {
+        target: Selector<String>,
+        attribute: RStr,
+        id: RStr,
+    },
     /// set a score to a value
     ScoreSet {
         target: RStr,
@@ -72,18 +117,43 @@ pub enum Command {
     },
     /// get NBT data
     DataGet(NbtLocation),
+    /// get NBT data multiplied by a scale
+    DataGetScaled { target: NbtLocation, scale: i32 },
     /// set NBT data to a constant
     DataSetValue { target: NbtLocation, value: RStr },
     /// execute a command with certain options
     Execute {
         options: Vec<ExecuteOption>,
-        cmd: Box<Command>,
+        cmd: Box<Self>,
+    },
+    /// create a bossbar with a JSON display name
+    BossbarAdd { id: RStr, name: RStr },
+    /// set a property of a bossbar, like its `value` or `players`
+    BossbarSet {
+        id: RStr,
+        property: RStr,
+        value: RStr,
+    },
+    /// get a property of a bossbar
+    BossbarGet { id: RStr, property: RStr },
+    /// create a team with a display name
+    TeamAdd { name: RStr, display_name: RStr },
+    /// set an option of a team, like its `color` or `prefix`
+    TeamModify {
+        name: RStr,
+        option: RStr,
+        value: RStr,
+    },
+    /// add entities to a team
+    TeamJoin { name: RStr, members: Selector<String> },
+    /// remove entities from whatever team they're on
+    TeamLeave { members: Selector<String> },
+    /// add or remove a tag from the target
+    Tag {
+        target: Selector<String>,
+        add: bool,
+        tag: RStr,
     },
-    // Tag {
-    //     target: Selector<String>,
-    //     add: bool,
-    //     tag: RStr,
-    // },
     /// teleport the selected entity(ies) to the destination
     Teleport {
         target: Selector<String>,
@@ -104,6 +174,43 @@ pub enum Command {
         pitch: f32,
         min_volume: f32,
     },
+    /// place a block, optionally with a mode like `destroy`
+    SetBlock {
+        pos: Coordinate,
+        block: RStr,
+        mode: Option<RStr>,
+    },
+    /// fill a region with a block, optionally with a mode like `hollow` or `replace {filter}`
+    Fill {
+        start: Coordinate,
+        end: Coordinate,
+        block: RStr,
+        mode: Option<RStr>,
+    },
+    /// copy a region to a destination
+    Clone {
+        start: Coordinate,
+        end: Coordinate,
+        destination: Coordinate,
+        mask: RStr,
+        mode: RStr,
+    },
+    /// summon an entity, optionally with NBT
+    Summon {
+        entity: RStr,
+        pos: Coordinate,
+        nbt: Option<Nbt>,
+    },
+    /// show particles, optionally only to some viewers
+    Particle {
+        kind: RStr,
+        pos: Coordinate,
+        delta: [f32; 3],
+        speed: f32,
+        count: i32,
+        force: bool,
+        viewers: Option<Selector<String>>,
+    },
     /// damage the target
     Damage {
         target: Selector<String>,
@@ -118,8 +225,15 @@ impl Hash for Command {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         core::mem::discriminant(self).hash(state);
         match self {
-            Self::Raw(str) | Self::Function(str) => str.hash(state),
+            Self::Raw(str) | Self::Function(str) | Self::ScheduleClear(str) => str.hash(state),
             Self::TellRaw(sel, content) => (sel, content).hash(state),
+            Self::Title { target, kind, text } => (target, kind, text).hash(state),
+            Self::TitleTimes {
+                target,
+                fade_in,
+                stay,
+                fade_out,
+            } => (target, fade_in, stay, fade_out).hash(state),
             Self::EffectGive {
                 target,
                 effect,
@@ -143,6 +257,29 @@ impl Hash for Command {
                 value,
             } => (target, objective, value).hash(state),
             Self::ScoreGet { target, objective } => (target, objective).hash(state),
+            Self::AttributeGet {
+                target,
+                attribute,
+                scale,
+            } => (target, attribute, scale).hash(state),
+            Self::AttributeBaseSet {
+                target,
+                attribute,
+                value,
+            } => (target, attribute, value.to_bits()).hash(state),
+            Self::AttributeModifierAdd {
+                target,
+                attribute,
+                id,
+                name,
+                amount,
+                operation,
+            } => (target, attribute, id, name, amount.to_bits(), operation).hash(state),
+            Self::AttributeModifierRemove {
+                target,
+                attribute,
+                id,
+            } => (target, attribute, id).hash(state),
             Self::ScoreOperation {
                 target,
                 target_objective,
@@ -170,8 +307,25 @@ impl Hash for Command {
             Self::XpGet { target, levels } => (target, levels).hash(state),
             Self::DataSetFrom { target, src } => (target, src).hash(state),
             Self::DataGet(target) => target.hash(state),
+            Self::DataGetScaled { target, scale } => (target, scale).hash(state),
             Self::DataSetValue { target, value } => (target, value).hash(state),
             Self::Execute { options, cmd } => (options, cmd).hash(state),
+            Self::Tag { target, add, tag } => (target, add, tag).hash(state),
+            Self::BossbarAdd { id, name } => (id, name).hash(state),
+            Self::BossbarSet {
+                id,
+                property,
+                value,
+            } => (id, property, value).hash(state),
+            Self::BossbarGet { id, property } => (id, property).hash(state),
+            Self::TeamAdd { name, display_name } => (name, display_name).hash(state),
+            Self::TeamModify {
+                name,
+                option,
+                value,
+            } => (name, option, value).hash(state),
+            Self::TeamJoin { name, members } => (name, members).hash(state),
+            Self::TeamLeave { members } => members.hash(state),
             Self::Teleport {
                 target,
                 destination,
@@ -198,6 +352,39 @@ impl Hash for Command {
                 min_volume.to_bits(),
             )
                 .hash(state),
+            Self::SetBlock { pos, block, mode } => (pos, block, mode).hash(state),
+            Self::Fill {
+                start,
+                end,
+                block,
+                mode,
+            } => (start, end, block, mode).hash(state),
+            Self::Clone {
+                start,
+                end,
+                destination,
+                mask,
+                mode,
+            } => (start, end, destination, mask, mode).hash(state),
+            Self::Summon { entity, pos, nbt } => (entity, pos, nbt).hash(state),
+            Self::Particle {
+                kind,
+                pos,
+                delta,
+                speed,
+                count,
+                force,
+                viewers,
+            } => (
+                kind,
+                pos,
+                delta.map(f32::to_bits),
+                speed.to_bits(),
+                count,
+                force,
+                viewers,
+            )
+                .hash(state),
             Self::Damage {
                 target,
                 amount,
@@ -209,11 +396,24 @@ impl Hash for Command {
 }
 
 impl Command {
+    /// set a score to a value, even 0, since `ScoreSet` resets the score for 0 instead
+    pub fn score_init(target: &str, objective: &str, value: i32) -> Self {
+        Self::Raw(format!("scoreboard players set {target} {objective} {value}").into())
+    }
+
     /// Convert the command to a string within the given namespace
+    #[allow(clippy::too_many_lines)]
     pub fn stringify(&self, namespace: &str) -> String {
         match self {
             Self::Raw (cmd) => cmd.replace("<NAMESPACE>", namespace),
             Self::TellRaw(sel, raw) => format!("tellraw {sel} {raw}"),
+            Self::Title { target, kind, text } => format!("title {target} {kind} {text}"),
+            Self::TitleTimes {
+                target,
+                fade_in,
+                stay,
+                fade_out,
+            } => format!("title {target} times {fade_in} {stay} {fade_out}"),
             Self::EffectGive {
                 target,
                 effect,
@@ -227,12 +427,28 @@ impl Command {
             }
             Self::Kill (target) => format!("kill {target}"),
             Self::Function (func) => format!("function {namespace}:{}", fmt_mc_ident(func)),
-            Self::Schedule { func, time, replace } => format!("schedule function {func} {time} {}", if *replace { "replace" } else { "append" }),
-            // Self::Tag { target, add, tag } => format!("tag {} {target} {tag}", if *add {
-            //     "add"
-            // } else {
-            //     "remove"
-            // }),
+            Self::Schedule { func, time, replace } => format!("schedule function {} {time} {}", namespaced(func, namespace), if *replace { "replace" } else { "append" }),
+            Self::AttributeGet { target, attribute, scale: 1 } => format!("attribute {target} {attribute} get"),
+            Self::AttributeGet { target, attribute, scale } => format!("attribute {target} {attribute} get {scale}"),
+            Self::AttributeBaseSet { target, attribute, value } => format!("attribute {target} {attribute} base set {value}"),
+            Self::AttributeModifierAdd { target, attribute, id, name, amount, operation } => name.as_ref().map_or_else(
+                || format!("attribute {target} {attribute} modifier add {id} {amount} {operation}"),
+                |name| format!("attribute {target} {attribute} modifier add {id} {name} {amount} {operation}"),
+            ),
+            Self::AttributeModifierRemove { target, attribute, id } => format!("attribute {target} {attribute} modifier remove {id}"),
+            Self::ScheduleClear(func) => format!("schedule clear {}", namespaced(func, namespace)),
+            Self::BossbarAdd { id, name } => format!("bossbar add {} {name}", namespaced(id, namespace)),
+            Self::BossbarSet { id, property, value } => format!("bossbar set {} {property} {value}", namespaced(id, namespace)),
+            Self::BossbarGet { id, property } => format!("bossbar get {} {property}", namespaced(id, namespace)),
+            Self::TeamAdd { name, display_name } => format!("team add {name} {display_name}"),
+            Self::TeamModify { name, option, value } => format!("team modify {name} {option} {value}"),
+            Self::TeamJoin { name, members } => format!("team join {name} {members}"),
+            Self::TeamLeave { members } => format!("team leave {members}"),
+            Self::Tag { target, add, tag } => format!("tag {target} {} {tag}", if *add {
+                "add"
+            } else {
+                "remove"
+            }),
             Self::ScoreSet {
                 target: player,
                 objective: score,
@@ -266,13 +482,37 @@ impl Command {
             Self::Teleport { target, destination } => format!("tp {target} {destination}"),
             Self::TeleportTo { target, destination } => format!("tp {target} {destination}"),
             Self::Sound { sound, source, target, pos, volume, pitch, min_volume } => format!("playsound {sound} {source} {target} {pos} {volume} {pitch} {min_volume}"),
+            Self::SetBlock { pos, block, mode } => mode.as_ref().map_or_else(
+                || format!("setblock {pos} {block}"),
+                |mode| format!("setblock {pos} {block} {mode}"),
+            ),
+            Self::Fill { start, end, block, mode } => mode.as_ref().map_or_else(
+                || format!("fill {start} {end} {block}"),
+                |mode| format!("fill {start} {end} {block} {mode}"),
+            ),
+            Self::Clone { start, end, destination, mask, mode } => format!("clone {start} {end} {destination} {mask} {mode}"),
+            Self::Summon { entity, pos, nbt } => nbt.as_ref().map_or_else(
+                || format!("summon {entity} {pos}"),
+                |nbt| format!("summon {entity} {pos} {nbt}"),
+            ),
+            Self::Particle { kind, pos, delta: [dx, dy, dz], speed, count, force, viewers } => {
+                let mut particle = format!("particle {kind} {pos} {dx} {dy} {dz} {speed} {count}");
+                if *force || viewers.is_some() {
+                    particle.push_str(if *force { " force" } else { " normal" });
+                }
+                if let Some(viewers) = viewers {
+                    let _ = write!(particle, " {viewers}");
+                }
+                particle
+            }
             Self::Damage { target, amount, damage_type, attacker } => format!("damage {target} {amount} {damage_type} by {attacker}"),
             Self::XpAdd { target, amount, levels } => format!("xp add {target} {amount} {}", if *levels { "levels" } else {"points"}),
             Self::XpSet { target, amount, levels } => format!("xp set {target} {amount} {}", if *levels { "levels"} else {"points"}),
             Self::XpGet { target, levels } => format!("xp query {target} {}", if *levels { "levels"} else {"points"}),
             Self::DataSetFrom { target, src } => format!("data modify {} set from {}", target.stringify(namespace), src.stringify(namespace)),
             Self::DataSetValue { target, value } => format!("data modify {} set value {value}", target.stringify(namespace)),
-            Self::DataGet (target) => format!("data get {}", target.stringify(namespace))
+            Self::DataGet (target) => format!("data get {}", target.stringify(namespace)),
+            Self::DataGetScaled { target, scale } => format!("data get {} {scale}", target.stringify(namespace))
         }
     }
 
@@ -316,7 +556,7 @@ impl Command {
                         let func_name: RStr = hash.into();
                         // TODO: This does not work. It will overshadow different versions because this fn has historically been called
                         // once per version. I should probably make a map internally or something but that seems really hard.
-                        inner = cmd.clone();
+                        inner.clone_from(&cmd);
                         let func = Self::Function(func_name);
                         if options.is_empty() {
                             func
@@ -1,6 +1,6 @@
 use std::hash::Hash;
 
-use super::{Coordinate, NbtLocation, Operation, RStr, Selector};
+use super::{fmt_mc_ident, Coordinate, NbtLocation, Operation, RStr, Selector};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ExecuteOption {
@@ -32,12 +32,19 @@ pub enum ExecuteOption {
         objective: RStr,
         is_success: bool,
     },
-    /// store a result in NBT
+    /// store a result in NBT as a type like `float` or `double`
     StoreNBT {
         location: NbtLocation,
         is_success: bool,
+        data_type: RStr,
         scale: f32,
     },
+    /// store a result in a bossbar's value or max
+    StoreBossbar {
+        id: RStr,
+        max: bool,
+        is_success: bool,
+    },
     /// change who `@s` is
     As(Selector<String>),
     /// change where the command executes
@@ -53,6 +60,14 @@ pub enum ExecuteOption {
     },
     /// choose a specific position
     Positioned(Coordinate),
+    /// move to the position of an entity
+    PositionedAs(Selector<String>),
+    /// move to the top of a heightmap
+    PositionedOver(RStr),
+    /// change the dimension the command executes in
+    In(RStr),
+    /// snap the position to the block grid along some axes
+    Align(RStr),
     /// anchored eyes|feet
     Anchored(RStr),
     /// facing an entity
@@ -65,6 +80,51 @@ pub enum ExecuteOption {
         pos: Coordinate,
         value: RStr,
     },
+    /// Region matches another region
+    IfBlocks {
+        invert: bool,
+        start: Coordinate,
+        end: Coordinate,
+        destination: Coordinate,
+        masked: bool,
+    },
+    /// Entity or storage NBT exists
+    IfData {
+        invert: bool,
+        location: NbtLocation,
+    },
+    /// Block entity NBT exists
+    IfBlockData {
+        invert: bool,
+        pos: Coordinate,
+        path: RStr,
+    },
+    /// Predicate passes
+    IfPredicate {
+        invert: bool,
+        predicate: RStr,
+    },
+    /// Biome at a position matches id or tag
+    IfBiome {
+        invert: bool,
+        pos: Coordinate,
+        biome: RStr,
+    },
+    /// Executing in a dimension
+    IfDimension {
+        invert: bool,
+        dimension: RStr,
+    },
+    /// Chunk at a position is fully loaded
+    IfLoaded {
+        invert: bool,
+        pos: Coordinate,
+    },
+    /// Function returns a non-zero value
+    IfFunction {
+        invert: bool,
+        function: RStr,
+    },
     /// Change `@s` to an entity with a certain relationship to current `@s`
     On(RStr),
     /// summon an entity of type `ident` and set it to `@s`
@@ -107,11 +167,18 @@ impl Hash for ExecuteOption {
             Self::StoreNBT {
                 location,
                 is_success,
+                data_type,
                 scale,
-            } => (location, is_success, scale.to_bits()).hash(state),
+            } => (location, is_success, data_type, scale.to_bits()).hash(state),
+            Self::StoreBossbar {
+                id,
+                max,
+                is_success,
+            } => (id, max, is_success).hash(state),
             Self::As(selector)
             | Self::At(selector)
             | Self::RotatedAs(selector)
+            | Self::PositionedAs(selector)
             | Self::FacingEntity(selector) => selector.hash(state),
             Self::Rotated {
                 yaw_rel,
@@ -121,7 +188,35 @@ impl Hash for ExecuteOption {
             } => (yaw_rel, yaw.to_bits(), pitch_rel, pitch.to_bits()).hash(state),
             Self::FacingPos(pos) | Self::Positioned(pos) => pos.hash(state),
             Self::IfBlock { invert, pos, value } => (invert, pos, value).hash(state),
-            Self::Anchored(ident) | Self::On(ident) | Self::Summon(ident) => {
+            Self::IfBlocks {
+                invert,
+                start,
+                end,
+                destination,
+                masked,
+            } => (invert, start, end, destination, masked).hash(state),
+            Self::IfData { invert, location } => (invert, location).hash(state),
+            Self::IfBlockData { invert, pos, path } => (invert, pos, path).hash(state),
+            Self::IfBiome { invert, pos, biome } => (invert, pos, biome).hash(state),
+            Self::IfLoaded { invert, pos } => (invert, pos).hash(state),
+            Self::IfPredicate {
+                invert,
+                predicate: ident,
+            }
+            | Self::IfDimension {
+                invert,
+                dimension: ident,
+            }
+            | Self::IfFunction {
+                invert,
+                function: ident,
+            } => (invert, ident).hash(state),
+            Self::Anchored(ident)
+            | Self::On(ident)
+            | Self::Summon(ident)
+            | Self::PositionedOver(ident)
+            | Self::In(ident)
+            | Self::Align(ident) => {
                 ident.hash(state);
             }
         }
@@ -129,6 +224,7 @@ impl Hash for ExecuteOption {
 }
 
 impl ExecuteOption {
+    #[allow(clippy::too_many_lines)]
     pub fn stringify(&self, namespace: &str) -> String {
         match self {
             Self::IfScoreMatches {
@@ -181,18 +277,71 @@ impl ExecuteOption {
             Self::StoreNBT {
                 location,
                 is_success,
+                data_type,
                 scale,
             } => {
                 format!(
-                    "store {} {} float {scale}",
+                    "store {} {} {data_type} {scale}",
                     if *is_success { "success" } else { "result" },
                     location.stringify(namespace)
                 )
             }
+            Self::StoreBossbar {
+                id,
+                max,
+                is_success,
+            } => format!(
+                "store {} bossbar {} {}",
+                if *is_success { "success" } else { "result" },
+                namespaced(id, namespace),
+                if *max { "max" } else { "value" }
+            ),
             Self::IfBlock { invert, pos, value } => format!(
                 "{} block {pos} {value}",
                 if *invert { "unless" } else { "if" }
             ),
+            Self::IfBlocks {
+                invert,
+                start,
+                end,
+                destination,
+                masked,
+            } => format!(
+                "{} blocks {start} {end} {destination} {}",
+                if *invert { "unless" } else { "if" },
+                if *masked { "masked" } else { "all" }
+            ),
+            Self::IfData { invert, location } => format!(
+                "{} data {}",
+                if *invert { "unless" } else { "if" },
+                location.stringify(namespace)
+            ),
+            Self::IfBlockData { invert, pos, path } => format!(
+                "{} data block {pos} {path}",
+                if *invert { "unless" } else { "if" }
+            ),
+            Self::IfPredicate { invert, predicate } => format!(
+                "{} predicate {}",
+                if *invert { "unless" } else { "if" },
+                namespaced(predicate, namespace)
+            ),
+            Self::IfBiome { invert, pos, biome } => format!(
+                "{} biome {pos} {biome}",
+                if *invert { "unless" } else { "if" }
+            ),
+            Self::IfDimension { invert, dimension } => format!(
+                "{} dimension {dimension}",
+                if *invert { "unless" } else { "if" }
+            ),
+            Self::IfLoaded { invert, pos } => format!(
+                "{} loaded {pos}",
+                if *invert { "unless" } else { "if" }
+            ),
+            Self::IfFunction { invert, function } => format!(
+                "{} function {}",
+                if *invert { "unless" } else { "if" },
+                namespaced(function, namespace)
+            ),
             Self::As(selector) => format!("as {selector}"),
             Self::At(selector) => format!("at {selector}"),
             Self::RotatedAs(selector) => format!("rotated as {selector}"),
@@ -217,6 +366,10 @@ impl ExecuteOption {
                 },
             ),
             Self::Positioned(pos) => format!("positioned {pos}"),
+            Self::PositionedAs(selector) => format!("positioned as {selector}"),
+            Self::PositionedOver(heightmap) => format!("positioned over {heightmap}"),
+            Self::In(dimension) => format!("in {dimension}"),
+            Self::Align(axes) => format!("align {axes}"),
             Self::FacingEntity(selector) => format!("facing entity {selector}"),
             Self::FacingPos(pos) => format!("facing {pos}"),
             Self::Anchored(ident) => format!("anchored {ident}"),
@@ -225,3 +378,12 @@ impl ExecuteOption {
         }
     }
 }
+
+/// put a resource location in this pack's namespace unless it already has one
+pub fn namespaced(ident: &str, namespace: &str) -> String {
+    if ident.contains(':') {
+        String::from(ident)
+    } else {
+        format!("{namespace}:{}", fmt_mc_ident(ident))
+    }
+}
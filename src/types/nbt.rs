@@ -25,14 +25,15 @@ macro_rules! nbt {
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Nbt {
-    Object(BTreeMap<RStr, Nbt>),
-    Array(Vec<Nbt>),
+    Object(BTreeMap<RStr, Self>),
+    Array(Vec<Self>),
     String(RStr),
     Byte(i8),
     Integer(i32),
     Float(f32),
     // Double(f64),
     Boolean(bool),
+    IntArray(Vec<i32>),
 }
 
 impl Default for Nbt {
@@ -64,6 +65,13 @@ impl Display for Nbt {
             Self::Float(float) => write!(f, "{float}f"),
             // Self::Double(float) => write!(f, "{float}"),
             Self::Boolean(bool) => write!(f, "{bool}"),
+            Self::IntArray(arr) => {
+                write!(f, "[I;")?;
+                for (i, int) in arr.iter().enumerate() {
+                    write!(f, "{}{int}", if i == 0 { " " } else { ", " })?;
+                }
+                write!(f, "]")
+            }
         }
     }
 }
@@ -80,6 +88,7 @@ impl Hash for Nbt {
             Self::Float(float) => float.to_bits().hash(state),
             // Self::Double(double) => double.to_bits().hash(state),
             Self::Boolean(bool) => bool.hash(state),
+            Self::IntArray(arr) => arr.hash(state),
         }
     }
 }
@@ -126,6 +135,7 @@ impl Nbt {
             Self::Float(float) => format!("{float}"),
             // Self::Double(double) => format!("{double}"),
             Self::Boolean(bool) => format!("{bool}"),
+            Self::IntArray(arr) => format!("{arr:?}").replace(' ', ""),
         }
     }
 
@@ -169,6 +179,9 @@ impl TryFrom<&Syntax> for Nbt {
             Syntax::String(str) => Ok(Self::String(str.clone())),
             Syntax::Integer(num) => Ok(Self::Integer(*num)),
             Syntax::Float(float) => Ok(Self::Float(*float)),
+            Syntax::Identifier(bool) if matches!(&**bool, "true" | "false") => {
+                Ok(Self::Boolean(&**bool == "true"))
+            }
             Syntax::Unit => Ok(Self::default()),
             other => Err(format!("Can't make nbt from {other:?}")),
         }
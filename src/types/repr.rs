@@ -2,19 +2,54 @@ use std::collections::{BTreeMap, BTreeSet};
 
 use crate::{types::prelude::*, Config};
 
+/// first pack format where items use components instead of a `tag`
+pub const ITEM_COMPONENT_VERSION: u8 = 41;
+
 #[derive(Debug, Clone)]
 pub struct Item {
     pub name: RStr,
     pub base: RStr,
     pub nbt: Nbt,
+    /// typed properties like lore and enchantments,
+    /// as tag NBT in the base and as item components from [`ITEM_COMPONENT_VERSION`] on
+    pub properties: Versioned<BTreeMap<RStr, Nbt>>,
     /// function that runs when the item is consumed
     pub on_consume: VecCmd,
     /// function that runs when the item is used
     pub on_use: VecCmd,
     /// function that runs every tick while the item is being used
     pub while_using: VecCmd,
+    /// ticks after `on_use` runs before it can run again, or 0 for no cooldown
+    pub cooldown: i32,
+    /// function that runs when the item is used during its cooldown
+    pub on_cooldown: VecCmd,
+    /// whether the cooldown is shown in the actionbar
+    pub show_cooldown: bool,
+    /// function that runs when the item is dropped, with the item entity tagged `<item>_dropped`
+    pub on_drop: VecCmd,
+    /// function that runs when the item is picked up
+    pub on_pickup: VecCmd,
+    /// uses before the item breaks, or 0 for no custom durability
+    pub durability: i32,
+    /// the item's lore as text components, kept above the durability line when it's updated
+    pub lore: Vec<Nbt>,
+    /// sound that plays when the item breaks
+    pub break_sound: RStr,
+    /// function that runs when the item breaks
+    pub on_break: VecCmd,
+    /// function that runs when the holder hits an entity, which is tagged `<item>_victim`
+    pub on_hit: VecCmd,
+    /// function that runs when the holder kills an entity; the victim is already dead, so it can't be selected
+    pub on_kill: VecCmd,
+    /// function that runs when the holder is hurt
+    pub on_hurt: VecCmd,
+    /// function that runs when the holder breaks a block with the item
+    pub on_block_break: VecCmd,
     // function that runs every tick while the item is in the given slot
     pub slot_checks: Vec<(i8, VecCmd)>,
+    /// functions that run once when the item is put in and taken out of a slot, by slot name.
+    /// the slot is `None` for the selected hotbar slot
+    pub equip_events: BTreeMap<RStr, (Option<i8>, VecCmd, VecCmd)>,
 }
 
 impl Default for Item {
@@ -23,25 +58,107 @@ impl Default for Item {
             name: String::new().into(),
             base: String::new().into(),
             nbt: Nbt::default(),
+            properties: Versioned::default(),
             on_consume: VecCmd::default(),
             on_use: VecCmd::default(),
             while_using: VecCmd::default(),
+            cooldown: 0,
+            on_cooldown: VecCmd::default(),
+            show_cooldown: false,
+            on_drop: VecCmd::default(),
+            on_pickup: VecCmd::default(),
+            durability: 0,
+            lore: Vec::new(),
+            break_sound: "minecraft:entity.item.break".into(),
+            on_break: VecCmd::default(),
+            on_hit: VecCmd::default(),
+            on_kill: VecCmd::default(),
+            on_hurt: VecCmd::default(),
+            on_block_break: VecCmd::default(),
             slot_checks: Vec::new(),
+            equip_events: BTreeMap::new(),
+        }
+    }
+}
+
+/// a custom block declared with `@block`, placed with the item of the same name
+/// and shown by an item display entity that holds its state
+#[derive(Debug, Clone)]
+pub struct Block {
+    /// name of the item that places the block, and that it drops when it's broken
+    pub name: RStr,
+    /// vanilla block that's set where it's placed; breaking it breaks the custom block
+    pub vanilla: RStr,
+    /// what the vanilla block drops without silk touch, if it isn't itself or nothing
+    pub vanilla_drop: Option<RStr>,
+    /// function that runs as the display entity when the block is placed
+    pub on_place: VecCmd,
+    /// function that runs as the display entity when the block is broken
+    pub on_break: VecCmd,
+    /// function that runs as the display entity when a player right clicks the block,
+    /// with the player tagged `<block>_interacting`
+    pub on_interact: VecCmd,
+    /// function that runs as each display entity every tick
+    pub tick: VecCmd,
+}
+
+impl Default for Block {
+    fn default() -> Self {
+        Self {
+            name: RStr::default(),
+            // spawners can be broken in survival, can be seen through, and don't drop anything
+            vanilla: "minecraft:spawner".into(),
+            vanilla_drop: None,
+            on_place: VecCmd::default(),
+            on_break: VecCmd::default(),
+            on_interact: VecCmd::default(),
+            tick: VecCmd::default(),
         }
     }
 }
 
+/// display settings of a scoreboard objective declared with `@objective`
+#[derive(Debug, Clone, Default)]
+pub struct ObjectiveDisplay {
+    /// display name as a JSON text component
+    pub name: Option<String>,
+    /// `integer` or `hearts`
+    pub render: Option<RStr>,
+    /// display slots the objective is shown in, like `sidebar`
+    pub slots: Vec<RStr>,
+}
+
 /// intermediate representation of most items and functions
 #[derive(Debug)]
 pub struct InterRepr {
     pub items: Vec<Item>,
+    /// custom blocks declared with `@block`
+    pub blocks: Vec<Block>,
     pub objectives: BTreeMap<RStr, RStr>,
+    /// objectives declared with `@objective`
+    pub objective_displays: BTreeMap<RStr, ObjectiveDisplay>,
+    /// commands creating each bossbar declared with `@bossbar`
+    pub bossbars: BTreeMap<RStr, Vec<Command>>,
+    /// commands creating each team declared with `@team`
+    pub teams: BTreeMap<RStr, Vec<Command>>,
     pub functions: BTreeMap<RStr, VecCmd>,
     pub advancements: BTreeMap<RStr, Nbt>,
-    pub recipes: BTreeMap<RStr, (String, RStr)>,
+    pub recipes: BTreeMap<RStr, (Nbt, RStr)>,
     pub loot_tables: BTreeMap<RStr, RStr>,
     pub constants: BTreeSet<i32>,
     pub custom_model_data: BTreeMap<RStr, BTreeMap<i32, String>>,
+    /// each enum's variants and their values, in declaration order
+    pub enums: BTreeMap<RStr, Vec<(RStr, i32)>>,
+    /// tags checked with `if @s has {tag}`, so the compiler can warn about ones that are never added
+    pub tested_tags: BTreeSet<RStr>,
+    /// tags added with `tag {selector} += {tag}`
+    pub added_tags: BTreeSet<RStr>,
+    /// objectives used before they were declared, checked once the whole program is interpreted
+    pub used_objectives: BTreeSet<RStr>,
+    /// tags marking the entities each enclosing `for .. in @selector` loop has yet to visit
+    pub for_each_tags: Vec<RStr>,
+    /// whether the statements interpreted so far in the innermost loop body can `break`
+    pub broke_loop: bool,
     // /// all of the standard library functions it uses
     // pub std_imports: BTreeSet<RStr>,
 }
@@ -53,17 +170,52 @@ impl InterRepr {
         objectives.insert(config.dummy_objective.clone(), "dummy".into());
         Self {
             items: Vec::new(),
+            blocks: Vec::new(),
             objectives,
+            objective_displays: BTreeMap::new(),
+            bossbars: BTreeMap::new(),
+            teams: BTreeMap::new(),
             functions: BTreeMap::new(),
             advancements: BTreeMap::new(),
             recipes: BTreeMap::new(),
             loot_tables: BTreeMap::new(),
             constants: BTreeSet::new(),
             custom_model_data: BTreeMap::new(),
+            enums: BTreeMap::new(),
+            tested_tags: BTreeSet::new(),
+            added_tags: BTreeSet::new(),
+            used_objectives: BTreeSet::new(),
+            for_each_tags: Vec::new(),
+            broke_loop: false,
             // std_imports: BTreeSet::new(),
         }
     }
 
+    /// register an objective used by the program; it's created as `dummy` at the end if it's never declared
+    pub fn use_objective(&mut self, objective: &RStr) {
+        if !self.objectives.contains_key(objective) {
+            self.used_objectives.insert(objective.clone());
+        }
+    }
+
+    /// create the objectives that were used but never declared, or error about them in strict mode
+    ///
+    /// this runs after the whole program is interpreted, so objectives can be declared after they're used
+    pub fn declare_used_objectives(&mut self, config: &Config) -> SResult<()> {
+        for objective in core::mem::take(&mut self.used_objectives) {
+            if self.objectives.contains_key(&objective) {
+                continue;
+            }
+            if config.strict_objectives {
+                return Err(format!(
+                    "Objective `{objective}` is used but never declared; declare it with `@objective {objective} {{ ... }}`"
+                ));
+            }
+            self.objectives.insert(objective, "dummy".into());
+        }
+        Ok(())
+    }
+
     /// add a custom model data given a base item and a new texture name
     pub fn add_custom_model_data(&mut self, path: RStr, number: i32, texture: String) {
         self.custom_model_data
@@ -77,18 +229,20 @@ impl InterRepr {
 #[derive(Debug, Clone, Default)]
 pub struct CompiledRepr {
     pub functions: BTreeMap<RStr, Versioned<String>>,
-    pub advancements: BTreeMap<RStr, String>,
-    pub recipes: BTreeMap<RStr, String>,
+    pub advancements: BTreeMap<RStr, Versioned<String>>,
+    pub recipes: BTreeMap<RStr, Versioned<String>>,
+    pub item_modifiers: BTreeMap<RStr, Versioned<String>>,
     pub loot_tables: BTreeMap<RStr, RStr>,
 }
 
 impl CompiledRepr {
     /// writes the .mcmeta file
-    pub fn new(loot_tables: BTreeMap<RStr, RStr>) -> Self {
+    pub const fn new(loot_tables: BTreeMap<RStr, RStr>) -> Self {
         Self {
             functions: BTreeMap::new(),
             advancements: BTreeMap::new(),
             recipes: BTreeMap::new(),
+            item_modifiers: BTreeMap::new(),
             loot_tables,
         }
     }
@@ -15,35 +15,44 @@ pub enum Syntax {
     /// A floating piece of text
     Identifier(RStr),
     /// An annotation invocation with the name and body of the annotation
-    Annotation(RStr, Box<Syntax>),
+    Annotation(RStr, Box<Self>),
     /// A list of key-value pairs
-    Object(BTreeMap<RStr, Syntax>),
+    Object(BTreeMap<RStr, Self>),
     /// A list of syntax elements
-    Array(Rc<[Syntax]>),
+    Array(Rc<[Self]>),
     /// A selector
-    Selector(Selector<Syntax>),
+    Selector(Selector<Self>),
     /// A selector with a colon and a score name
-    SelectorColon(Selector<Syntax>, RStr),
+    SelectorColon(Selector<Self>, RStr),
     /// A selector with a double colon and a special identifier
-    SelectorDoubleColon(Selector<Syntax>, RStr),
+    SelectorDoubleColon(Selector<Self>, RStr),
     /// A selector with an nbt path in the form of `@s.Inventory[42].tag`
-    SelectorNbt(Selector<Syntax>, NbtPath),
+    SelectorNbt(Selector<Self>, NbtPath),
     /// An identifier with an NBT path on the end
     NbtStorage(NbtPath),
     /// A binary operation like x += 2
     BinaryOp {
-        lhs: Box<Syntax>,
+        lhs: Box<Self>,
         operation: Operation,
-        rhs: Box<Syntax>,
+        rhs: Box<Self>,
     },
     /// A block of the form `positioned @s { ... }`
-    Block(BlockType, Box<Syntax>, Box<Syntax>),
+    Block(BlockType, Box<Self>, Box<Self>),
+    /// A vanilla `execute if` condition with its arguments, like `block (~ ~-1 ~) grass_block`
+    Condition(RStr, Rc<[Self]>),
     /// A string literal
     String(RStr),
     /// An integer literal
     Integer(i32),
     /// A range literal
     Range(Option<i32>, Option<i32>),
+    /// A range with any bounds and an optional step, like `0..<n step 2` in a `for` loop
+    ForRange {
+        start: Box<Self>,
+        end: Box<Self>,
+        inclusive: bool,
+        step: Option<i32>,
+    },
     /// A coordinate starting with ~
     WooglyCoord(f32),
     /// A coordinate starting with ^
@@ -72,12 +81,35 @@ impl Debug for Syntax {
                 rhs,
             } => write!(f, "{lhs:?} {op} {rhs:?}"),
             Self::Block(block_type, lhs, rhs) => write!(f, "{block_type} ({lhs:?}) {rhs:?}"),
+            Self::Condition(kind, args) => {
+                write!(f, "{kind}")?;
+                for arg in args.iter() {
+                    write!(f, " {arg:?}")?;
+                }
+                Ok(())
+            }
             Self::String(str) => write!(f, "\"{str}\""),
             Self::Integer(int) => write!(f, "{int}"),
             Self::Range(Some(lhs), Some(rhs)) => write!(f, "{lhs}..{rhs}"),
             Self::Range(Some(lhs), None) => write!(f, "{lhs}.."),
             Self::Range(None, Some(rhs)) => write!(f, "..{rhs}"),
             Self::Range(None, None) => write!(f, ".."),
+            Self::ForRange {
+                start,
+                end,
+                inclusive,
+                step,
+            } => {
+                write!(
+                    f,
+                    "{start:?}..{}{end:?}",
+                    if *inclusive { "=" } else { "<" }
+                )?;
+                if let Some(step) = step {
+                    write!(f, " step {step}")?;
+                }
+                Ok(())
+            }
             Self::WooglyCoord(coord) => write!(f, "~{coord}"),
             Self::CaretCoord(coord) => write!(f, "^{coord}"),
             Self::Float(float) => write!(f, "{float}"),
@@ -90,6 +122,8 @@ impl Debug for Syntax {
 #[strum(serialize_all = "snake_case")]
 pub enum BlockType {
     Advancement,
+    Actionbar,
+    Align,
     Anchored,
     As,
     AsAt,
@@ -97,6 +131,7 @@ pub enum BlockType {
     At,
     Case,
     Damage,
+    Default,
     Do,
     DoUntil,
     DoWhile,
@@ -104,12 +139,17 @@ pub enum BlockType {
     For,
     Function,
     If,
+    In,
     On,
     Positioned,
+    PositionedAs,
+    PositionedOver,
     Rotated,
+    Subtitle,
     Summon,
     Switch,
     Tellraw,
+    Title,
     // allow both versions to work
     #[strum(serialize = "tp", serialize = "teleport")]
     Tp,
@@ -164,14 +204,24 @@ impl Hash for Syntax {
                 lhs.hash(state);
                 rhs.hash(state);
             }
+            Self::Condition(kind, args) => {
+                kind.hash(state);
+                args.hash(state);
+            }
             Self::Integer(int) => int.hash(state),
             Self::Range(left, right) => {
                 left.hash(state);
                 right.hash(state);
             }
+            Self::ForRange {
+                start,
+                end,
+                inclusive,
+                step,
+            } => (start, end, inclusive, step).hash(state),
             // allow float to hash. NaNs are non-deterministic
             Self::Float(float) | Self::WooglyCoord(float) | Self::CaretCoord(float) => {
-                unsafe { &*(float as *const f32).cast::<u32>() }.hash(state);
+                unsafe { &*std::ptr::from_ref::<f32>(float).cast::<u32>() }.hash(state);
             }
             Self::Unit => {}
         }
@@ -194,6 +244,8 @@ pub enum DataLocation {
     SelectorNbt(Selector<Syntax>, NbtPath),
     /// A storage space with an NBT path
     NbtStorage(NbtPath),
+    /// A property of a bossbar, like `bossbar::fight.value`
+    Bossbar(RStr, RStr),
 }
 
 impl DataLocation {
@@ -203,11 +255,12 @@ impl DataLocation {
             Self::Selector(selector) | Self::SelectorColon(selector, _) => {
                 Ok(format!("{}", selector.stringify()?).into())
             }
-            Self::SelectorDoubleColon(_, _) | Self::SelectorNbt(_, _) | Self::NbtStorage(_) => {
-                Err(format!(
+            Self::SelectorDoubleColon(_, _)
+            | Self::SelectorNbt(_, _)
+            | Self::NbtStorage(_)
+            | Self::Bossbar(_, _) => Err(format!(
                 "{self:?} isn't a score. This is a compiler error. Please notify the developers"
-            ))
-            }
+            )),
         }
     }
 
@@ -215,11 +268,12 @@ impl DataLocation {
         match self {
             Self::Ident(_) | Self::Selector(_) => Ok(config.dummy_objective.clone()),
             Self::Colon(_, score) | Self::SelectorColon(_, score) => Ok(score.clone()),
-            Self::SelectorDoubleColon(_, _) | Self::SelectorNbt(_, _) | Self::NbtStorage(_) => {
-                Err(format!(
+            Self::SelectorDoubleColon(_, _)
+            | Self::SelectorNbt(_, _)
+            | Self::NbtStorage(_)
+            | Self::Bossbar(_, _) => Err(format!(
                 "{self:?} isn't a score. This is a compiler error. Please notify the developers"
-            ))
-            }
+            )),
         }
     }
 }
@@ -247,6 +301,19 @@ impl TryFrom<Syntax> for DataLocation {
             Syntax::SelectorDoubleColon(sel, ident) => Ok(Self::SelectorDoubleColon(sel, ident)),
             Syntax::SelectorNbt(sel, nbt) => Ok(Self::SelectorNbt(sel, nbt)),
             Syntax::NbtStorage(storage) => Ok(Self::NbtStorage(storage)),
+            Syntax::BinaryOp {
+                lhs,
+                operation: Operation::DoubleColon,
+                rhs,
+            } => match (*lhs, *rhs) {
+                (Syntax::Identifier(kind), Syntax::NbtStorage(path)) if &*kind == "bossbar" => {
+                    let [NbtPathPart::Ident(id), NbtPathPart::Ident(property)] = &path[..] else {
+                        return Err(())
+                    };
+                    Ok(Self::Bossbar(id.clone(), property.clone()))
+                }
+                _ => Err(()),
+            },
             _ => Err(()),
         }
     }
@@ -266,6 +333,14 @@ impl From<DataLocation> for Syntax {
             DataLocation::SelectorColon(sel, id) => Self::SelectorColon(sel, id),
             DataLocation::SelectorDoubleColon(sel, id) => Self::SelectorDoubleColon(sel, id),
             DataLocation::SelectorNbt(sel, nbt) => Self::SelectorNbt(sel, nbt),
+            DataLocation::Bossbar(id, property) => Self::BinaryOp {
+                lhs: Box::new(Self::Identifier("bossbar".into())),
+                operation: Operation::DoubleColon,
+                rhs: Box::new(Self::NbtStorage(vec![
+                    NbtPathPart::Ident(id),
+                    NbtPathPart::Ident(property),
+                ])),
+            },
         }
     }
 }
@@ -308,6 +383,8 @@ pub enum Operation {
     Swap,
     /// check if in range
     In,
+    /// check if an entity has a tag
+    Has,
     /// assignment for fixed-point decimals
     FpEq,
     /// addition for fixed-point decimals
@@ -344,6 +421,7 @@ impl Display for Operation {
                 Self::LCaret => "<",
                 Self::RCaret => ">",
                 Self::In => "in",
+                Self::Has => "has",
                 Self::FpEq => ".=",
                 Self::FpAddEq => ".+=",
                 Self::FpSubEq => ".-=",
@@ -381,10 +459,10 @@ impl TryFrom<Token> for Operation {
             Token::DotStarEq => Ok(Self::FpMulEq),
             Token::DotSlashEq => Ok(Self::FpDivEq),
             Token::Identifier(ident) => {
-                if ident.as_ref() == "in" {
-                    Ok(Self::In)
-                } else {
-                    Err(())
+                match ident.as_ref() {
+                    "in" => Ok(Self::In),
+                    "has" => Ok(Self::Has),
+                    _ => Err(()),
                 }
             }
             _ => Err(()),
@@ -15,7 +15,7 @@ impl<T: Clone> Versioned<Vec<T>> {
         self.extend(other.map(Some));
     }
 
-    pub fn is_empty(&self) -> bool {
+    pub const fn is_empty(&self) -> bool {
         self.base.is_empty()
     }
 }
@@ -72,7 +72,16 @@ impl<T> Versioned<T> {
 }
 
 impl<T: Clone> Versioned<T> {
-    pub fn map_with<O: Clone, F>(&mut self, func: F, mut other: Versioned<O>)
+    /// use `other` from pack format `version` on
+    pub fn switch_at(mut self, version: u8, other: Self) -> Self {
+        self.mods.retain(|v, _| *v < version);
+        self.mods.insert(version, other.get(version).clone());
+        self.mods
+            .extend(other.mods.into_iter().filter(|(v, _)| *v > version));
+        self
+    }
+
+    pub fn map_with<O: Clone, F>(&mut self, func: F, other: Versioned<O>)
     where
         F: Fn(&mut T, O),
     {
@@ -84,26 +93,16 @@ impl<T: Clone> Versioned<T> {
             .union(&other.mods.keys().copied().collect())
             .copied()
             .collect();
+        // new versions start from what was there before any were added
+        let snapshot = self.clone();
         for version in all_mods {
-            match (
-                self.mods.contains_key(&version),
-                other.mods.remove(&version),
-            ) {
-                (true, Some(other)) => func(self.mods.get_mut(&version).unwrap(), other),
-                (true, None) => func(
-                    self.mods.get_mut(&version).unwrap(),
-                    other.get(version).clone(),
-                ),
-                (false, Some(other)) => {
-                    let mut new = self.get(version).clone();
-                    func(&mut new, other);
-                    self.mods.insert(version, new);
-                }
-                (false, None) => {
-                    let mut new = self.get(version).clone();
-                    func(&mut new, other.get(version).clone());
-                    self.mods.insert(version, new);
-                }
+            let other_version = other.get(version).clone();
+            if let Some(this) = self.mods.get_mut(&version) {
+                func(this, other_version);
+            } else {
+                let mut new = snapshot.get(version).clone();
+                func(&mut new, other_version);
+                self.mods.insert(version, new);
             }
         }
         func(&mut self.base, other.base);
@@ -141,7 +140,7 @@ impl Versioned<String> {
         self.map_with(|mine, theirs| mine.push_str(&theirs), strs);
     }
 
-    pub fn is_empty(&self) -> bool {
+    pub const fn is_empty(&self) -> bool {
         self.base.is_empty()
     }
 }